@@ -4,5 +4,20 @@ pub mod client;
 /// a default implementation of [Client<Order>] around the original uniswapX api
 pub mod uniswap;
 
-/// implementaion of [OrderSubscriber] which wraps a [Client<Order>]
+/// the [subscriber::Subscriber] trait and its implementations, e.g. [subscriber::OrderSubscriber] which polls a [client::Client]
 pub mod subscriber;
+
+/// [retry::RetryClient], a [client::Client] wrapper with backoff, rate limiting and a circuit breaker
+pub mod retry;
+
+/// [merged::MergedClient], a [client::Client] that fans out to many sources
+pub mod merged;
+
+/// [stream::StreamingClient], a websocket or server sent events client that feeds a [subscriber::push::PushSubscriber]
+pub mod stream;
+
+/// [webhook::WebhookReceiver], receives orders pushed to a filler webhook
+pub mod webhook;
+
+#[cfg(test)]
+mod test_utils;
@@ -0,0 +1,215 @@
+use super::client::Client;
+use std::collections::HashSet;
+use uniswapx_sdk_core::order::{OrderMetadata, SignedOrder};
+
+#[allow(unused_imports)]
+use tracing::{debug, error, info, trace, warn};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// a [Client] over many sources, see [MergedClient::firehose]
+///
+/// ```ignore
+/// let client = MergedClient::new()
+///     .source("uniswap-mainnet", UniswapClient::new(1))
+///     .source("uniswap-arbitrum", UniswapClient::new(42161))
+///     .source("relay", relay_client);
+/// ```
+#[derive(Default)]
+pub struct MergedClient {
+    sources: Vec<(String, Box<dyn DynClient>)>,
+}
+
+/// every source failed, with the error from each
+#[derive(Debug)]
+pub struct MergedError {
+    pub errors: Vec<(String, BoxError)>,
+}
+
+/// an object safe [Client<SignedOrder>] with the error boxed
+#[async_trait::async_trait]
+trait DynClient: Send + Sync {
+    async fn firehose(&self) -> Result<Vec<SignedOrder>, BoxError>;
+}
+
+struct Erased<C>(C);
+
+#[async_trait::async_trait]
+impl<C: Client<SignedOrder>> DynClient for Erased<C> {
+    async fn firehose(&self) -> Result<Vec<SignedOrder>, BoxError> {
+        Ok(self.0.firehose().await?)
+    }
+}
+
+impl MergedClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add a source, when an order comes from many sources the one added first wins
+    pub fn source<C>(mut self, name: impl Into<String>, client: C) -> Self
+    where
+        C: Client<SignedOrder> + 'static,
+    {
+        self.sources.push((name.into(), Box::new(Erased(client))));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+}
+
+#[async_trait::async_trait]
+impl Client<SignedOrder> for MergedClient {
+    type ClientError = MergedError;
+
+    /// calls every source concurrently, de-duplicating the orders by order hash
+    /// and setting the [OrderMetadata::source] of each order to the name of its source
+    ///
+    /// failing sources are logged and skipped, this only errors if every source fails
+    async fn firehose(&self) -> Result<Vec<SignedOrder>, Self::ClientError> {
+        let results =
+            futures::future::join_all(self.sources.iter().map(|(_, client)| client.firehose()))
+                .await;
+
+        let mut seen = HashSet::new();
+        let mut orders = Vec::new();
+        let mut errors = Vec::new();
+
+        for ((name, _), result) in self.sources.iter().zip(results) {
+            match result {
+                Ok(source_orders) => {
+                    for order in source_orders {
+                        // not every source sets the chain id, so its not part of the key
+                        if seen.insert(order.order_hash()) {
+                            orders.push(tag(order, name));
+                        }
+                    }
+                }
+                Err(e) => {
+                    warn!("merged client: source {} failed: {}", name, e);
+                    errors.push((name.clone(), e));
+                }
+            }
+        }
+
+        if !self.sources.is_empty() && errors.len() == self.sources.len() {
+            return Err(MergedError { errors });
+        }
+
+        Ok(orders)
+    }
+}
+
+fn tag(order: SignedOrder, source: &str) -> SignedOrder {
+    let metadata = OrderMetadata {
+        source: Some(source.to_string()),
+        ..order.metadata.clone().unwrap_or_default()
+    };
+
+    order.with_metadata(metadata)
+}
+
+impl std::error::Error for MergedError {}
+
+impl std::fmt::Display for MergedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "All sources failed:")?;
+
+        for (name, e) in &self.errors {
+            write!(f, " {}: {};", name, e)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uniswapx_sdk_core::test_utils::{dutch, exclusive_dutch};
+
+    #[derive(Debug)]
+    struct TestError;
+
+    /// a source that returns `orders`, or fails if theres none
+    struct TestClient(Option<Vec<SignedOrder>>);
+
+    #[async_trait::async_trait]
+    impl Client<SignedOrder> for TestClient {
+        type ClientError = TestError;
+
+        async fn firehose(&self) -> Result<Vec<SignedOrder>, TestError> {
+            self.0.clone().ok_or(TestError)
+        }
+    }
+
+    impl std::error::Error for TestError {}
+
+    impl std::fmt::Display for TestError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "test error")
+        }
+    }
+
+    fn source(order: &SignedOrder) -> Option<&str> {
+        order.metadata.as_ref()?.source.as_deref()
+    }
+
+    #[tokio::test]
+    async fn dedups_by_order_hash_and_tags_the_first_source() {
+        let dutch = dutch().signed("0x".to_string());
+        let exclusive = exclusive_dutch().signed("0x".to_string());
+        let with_chain = dutch.clone().with_metadata(OrderMetadata {
+            chain_id: Some(1),
+            source: Some("upstream".to_string()),
+            ..Default::default()
+        });
+
+        // the second source doesnt set the chain id, its still the same order
+        let client = MergedClient::new()
+            .source("a", TestClient(Some(vec![with_chain])))
+            .source(
+                "b",
+                TestClient(Some(vec![dutch.clone(), exclusive.clone()])),
+            );
+
+        let orders = client.firehose().await.unwrap();
+        assert_eq!(orders.len(), 2);
+
+        assert_eq!(orders[0].order_hash(), dutch.order_hash());
+        assert_eq!(source(&orders[0]), Some("a"));
+        assert_eq!(orders[0].metadata.as_ref().unwrap().chain_id, Some(1));
+
+        assert_eq!(orders[1].order_hash(), exclusive.order_hash());
+        assert_eq!(source(&orders[1]), Some("b"));
+    }
+
+    #[tokio::test]
+    async fn failing_sources_are_skipped() {
+        let order = dutch().signed("0x".to_string());
+        let client = MergedClient::new()
+            .source("down", TestClient(None))
+            .source("up", TestClient(Some(vec![order])));
+
+        let orders = client.firehose().await.unwrap();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(source(&orders[0]), Some("up"));
+
+        let client = MergedClient::new()
+            .source("a", TestClient(None))
+            .source("b", TestClient(None));
+
+        let err = match client.firehose().await {
+            Err(e) => e,
+            Ok(_) => panic!("expected every source to fail"),
+        };
+        let names: Vec<_> = err.errors.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["a", "b"]);
+    }
+}
@@ -0,0 +1,561 @@
+use super::client::Client;
+use rand::Rng;
+use std::{future::Future, sync::Mutex, time::Duration};
+use tokio::time::Instant;
+
+#[allow(unused_imports)]
+use tracing::{debug, error, info, trace, warn};
+
+const DEFAULT_MAX_RETRIES: u32 = 5;
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// lets [RetryClient] decide what to do with a clients error
+pub trait RetryableError {
+    /// if the request could succeed if its tried again, e.g. timeouts, 429s and 5xxs
+    fn is_retryable(&self) -> bool;
+
+    /// how long the server asked us to wait, e.g. from a `Retry-After` header
+    fn retry_after(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// retries single requests
+///
+/// - exponential backoff with jitter, or the servers `Retry-After` if it sent one, up to the max backoff
+/// - an optional token bucket rate limit, every attempt takes a token
+/// - an optional circuit breaker that fails fast after repeated failures
+///
+/// ```ignore
+/// let policy = RetryPolicy::new()
+///     .max_retries(3)
+///     .rate_limit(5.0, 10)?
+///     .circuit_breaker(5, Duration::from_secs(60));
+///
+/// let client = UniswapClient::new(1).retry(policy);
+/// ```
+pub struct RetryPolicy {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    rate_limit: Option<Mutex<TokenBucket>>,
+    breaker: Option<Mutex<CircuitBreaker>>,
+}
+
+/// a [Client] wrapper that retries whole [Client::firehose] calls with a [RetryPolicy]
+///
+/// this is only right for clients that make a single request per call,
+/// clients that make many, e.g. [crate::uniswap::UniswapClient] walking pages,
+/// should apply the policy to each request, see [crate::uniswap::UniswapClient::retry]
+///
+/// ```ignore
+/// let client = RetryClient::new(client)
+///     .max_retries(3)
+///     .rate_limit(5.0, 10)?
+///     .circuit_breaker(5, Duration::from_secs(60));
+/// ```
+pub struct RetryClient<C> {
+    inner: C,
+    policy: RetryPolicy,
+}
+
+#[derive(Debug)]
+pub enum RetryError<E> {
+    /// the inner client failed with a non retryable error, or ran out of retries
+    Client(E),
+    /// the circuit breaker is open, no request was made
+    CircuitOpen { retry_in: Duration },
+}
+
+/// the rate passed to [RetryPolicy::rate_limit] isnt a positive, finite number of requests per second
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InvalidRateLimit(pub f64);
+
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    per_second: f64,
+    last_refill: Instant,
+}
+
+struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            rate_limit: None,
+            breaker: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the number of retries after the first attempt
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// the backoff starts at `initial` and doubles each retry, up to `max`, a `Retry-After` is also capped at `max`
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// allow `per_second` requests per second on average, with bursts of up to `burst` requests
+    ///
+    /// `per_second` must be positive and finite, a `burst` of zero is treated as one
+    pub fn rate_limit(mut self, per_second: f64, burst: u32) -> Result<Self, InvalidRateLimit> {
+        if !(per_second.is_finite() && per_second > 0.0) {
+            return Err(InvalidRateLimit(per_second));
+        }
+
+        let burst = burst.max(1) as f64;
+        self.rate_limit = Some(Mutex::new(TokenBucket {
+            capacity: burst,
+            tokens: burst,
+            per_second,
+            last_refill: Instant::now(),
+        }));
+
+        Ok(self)
+    }
+
+    /// fail fast for `cooldown` after `threshold` consecutive requests fail with a retryable error
+    pub fn circuit_breaker(mut self, threshold: u32, cooldown: Duration) -> Self {
+        self.breaker = Some(Mutex::new(CircuitBreaker {
+            threshold,
+            cooldown,
+            failures: 0,
+            open_until: None,
+        }));
+        self
+    }
+
+    /// make a request, retrying it while it fails with a retryable error
+    pub async fn call<T, E, F, Fut>(&self, mut request: F) -> Result<T, RetryError<E>>
+    where
+        E: RetryableError + std::fmt::Display,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        self.check_breaker()
+            .map_err(|retry_in| RetryError::CircuitOpen { retry_in })?;
+
+        let mut attempt = 0;
+        loop {
+            self.acquire().await;
+
+            match request().await {
+                Ok(res) => {
+                    self.record(true);
+                    return Ok(res);
+                }
+                Err(e) if e.is_retryable() && attempt < self.max_retries => {
+                    let wait = match e.retry_after() {
+                        Some(retry_after) => retry_after.min(self.max_backoff),
+                        None => self.backoff_for(attempt),
+                    };
+
+                    warn!(
+                        "retry: attempt {} failed, retrying in {:?}: {}",
+                        attempt + 1,
+                        wait,
+                        e
+                    );
+
+                    tokio::time::sleep(wait).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    // a non retryable error, e.g. a 400, means the server is up, so it doesnt trip the breaker
+                    if e.is_retryable() {
+                        self.record(false);
+                    }
+                    return Err(RetryError::Client(e));
+                }
+            }
+        }
+    }
+
+    /// the backoff before retry number `attempt`, with equal jitter
+    fn backoff_for(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff);
+
+        let half = backoff / 2;
+        half + half.mul_f64(rand::thread_rng().gen::<f64>())
+    }
+
+    /// waits for a token if theres a rate limit
+    async fn acquire(&self) {
+        let bucket = match &self.rate_limit {
+            Some(bucket) => bucket,
+            None => return,
+        };
+
+        loop {
+            let wait = bucket.lock().expect("rate limit lock").take();
+
+            match wait {
+                Some(wait) => tokio::time::sleep(wait).await,
+                None => return,
+            }
+        }
+    }
+
+    fn check_breaker(&self) -> Result<(), Duration> {
+        match &self.breaker {
+            Some(breaker) => breaker.lock().expect("breaker lock").check(),
+            None => Ok(()),
+        }
+    }
+
+    fn record(&self, success: bool) {
+        if let Some(breaker) = &self.breaker {
+            breaker.lock().expect("breaker lock").record(success);
+        }
+    }
+}
+
+impl<C> RetryClient<C> {
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            policy: RetryPolicy::default(),
+        }
+    }
+
+    /// see [RetryPolicy::max_retries]
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.policy = self.policy.max_retries(max_retries);
+        self
+    }
+
+    /// see [RetryPolicy::backoff]
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.policy = self.policy.backoff(initial, max);
+        self
+    }
+
+    /// see [RetryPolicy::rate_limit]
+    pub fn rate_limit(mut self, per_second: f64, burst: u32) -> Result<Self, InvalidRateLimit> {
+        self.policy = self.policy.rate_limit(per_second, burst)?;
+        Ok(self)
+    }
+
+    /// see [RetryPolicy::circuit_breaker]
+    pub fn circuit_breaker(mut self, threshold: u32, cooldown: Duration) -> Self {
+        self.policy = self.policy.circuit_breaker(threshold, cooldown);
+        self
+    }
+
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+}
+
+#[async_trait::async_trait]
+impl<T, C> Client<T> for RetryClient<C>
+where
+    T: Send + 'static,
+    C: Client<T>,
+    C::ClientError: RetryableError,
+{
+    type ClientError = RetryError<C::ClientError>;
+
+    async fn firehose(&self) -> Result<Vec<T>, Self::ClientError> {
+        self.policy.call(|| self.inner.firehose()).await
+    }
+}
+
+impl TokenBucket {
+    /// take a token, or return how long until one is available
+    fn take(&mut self) -> Option<Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+
+        self.tokens = (self.tokens + elapsed * self.per_second).min(self.capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.per_second,
+            ))
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// `Err` with the time left if the breaker is open
+    ///
+    /// once the cooldown has passed a request is let through, if it fails the breaker opens again
+    fn check(&self) -> Result<(), Duration> {
+        match self.open_until {
+            Some(until) if until > Instant::now() => Err(until - Instant::now()),
+            _ => Ok(()),
+        }
+    }
+
+    fn record(&mut self, success: bool) {
+        if success {
+            self.failures = 0;
+            self.open_until = None;
+            return;
+        }
+
+        self.failures += 1;
+        if self.failures >= self.threshold {
+            warn!(
+                "circuit breaker: {} consecutive failures, opening for {:?}",
+                self.failures, self.cooldown
+            );
+            self.open_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+}
+
+impl std::error::Error for InvalidRateLimit {}
+
+impl std::fmt::Display for InvalidRateLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid rate limit: {} requests per second", self.0)
+    }
+}
+
+impl<E: std::error::Error> std::error::Error for RetryError<E> {}
+
+impl<E: std::error::Error> std::fmt::Display for RetryError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RetryError::Client(e) => write!(f, "Client error: {}", e),
+            RetryError::CircuitOpen { retry_in } => {
+                write!(f, "Circuit open, retry in {:?}", retry_in)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        collections::VecDeque,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+
+    #[derive(Debug)]
+    struct TestError {
+        retryable: bool,
+        retry_after: Option<Duration>,
+    }
+
+    /// a client that returns `responses` in order, then succeeds
+    #[derive(Default)]
+    struct TestClient {
+        responses: Mutex<VecDeque<TestError>>,
+        calls: AtomicU32,
+    }
+
+    const RETRYABLE: TestError = TestError {
+        retryable: true,
+        retry_after: None,
+    };
+
+    const NOT_RETRYABLE: TestError = TestError {
+        retryable: false,
+        retry_after: None,
+    };
+
+    impl TestClient {
+        fn failing(errors: impl IntoIterator<Item = TestError>) -> Self {
+            Self {
+                responses: Mutex::new(errors.into_iter().collect()),
+                calls: AtomicU32::new(0),
+            }
+        }
+
+        fn calls(&self) -> u32 {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Client<u32> for TestClient {
+        type ClientError = TestError;
+
+        async fn firehose(&self) -> Result<Vec<u32>, TestError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+
+            match self.responses.lock().unwrap().pop_front() {
+                Some(e) => Err(e),
+                None => Ok(vec![call]),
+            }
+        }
+    }
+
+    impl RetryableError for TestError {
+        fn is_retryable(&self) -> bool {
+            self.retryable
+        }
+
+        fn retry_after(&self) -> Option<Duration> {
+            self.retry_after
+        }
+    }
+
+    impl std::error::Error for TestError {}
+
+    impl std::fmt::Display for TestError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self)
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_retryable_errors() {
+        let client = RetryClient::new(TestClient::failing([RETRYABLE, RETRYABLE])).max_retries(2);
+
+        assert_eq!(client.firehose().await.unwrap(), vec![2]);
+        assert_eq!(client.inner().calls(), 3);
+
+        let client = RetryClient::new(TestClient::failing([RETRYABLE, RETRYABLE])).max_retries(1);
+
+        assert!(matches!(
+            client.firehose().await,
+            Err(RetryError::Client(TestError {
+                retryable: true,
+                ..
+            }))
+        ));
+        assert_eq!(client.inner().calls(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn doesnt_retry_other_errors() {
+        let client = RetryClient::new(TestClient::failing([NOT_RETRYABLE]));
+
+        assert!(matches!(
+            client.firehose().await,
+            Err(RetryError::Client(TestError {
+                retryable: false,
+                ..
+            }))
+        ));
+        assert_eq!(client.inner().calls(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waits_for_retry_after_up_to_the_max_backoff() {
+        let retry_after = |secs| TestError {
+            retryable: true,
+            retry_after: Some(Duration::from_secs(secs)),
+        };
+        let client = RetryClient::new(TestClient::failing([retry_after(3), retry_after(60)]))
+            .backoff(Duration::from_millis(1), Duration::from_secs(10));
+
+        let start = Instant::now();
+        client.firehose().await.unwrap();
+
+        assert_eq!(start.elapsed(), Duration::from_secs(13));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_limit_spaces_out_requests_after_a_burst() {
+        let client = RetryClient::new(TestClient::default())
+            .rate_limit(2.0, 3)
+            .unwrap();
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            client.firehose().await.unwrap();
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        for _ in 0..4 {
+            client.firehose().await.unwrap();
+        }
+        assert_eq!(start.elapsed(), Duration::from_secs(2));
+        assert_eq!(client.inner().calls(), 7);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn circuit_breaker_opens_and_half_opens() {
+        let cooldown = Duration::from_secs(60);
+        let client = RetryClient::new(TestClient::failing([RETRYABLE, RETRYABLE, RETRYABLE]))
+            .max_retries(0)
+            .circuit_breaker(2, cooldown);
+
+        assert!(matches!(
+            client.firehose().await,
+            Err(RetryError::Client(_))
+        ));
+        assert!(matches!(
+            client.firehose().await,
+            Err(RetryError::Client(_))
+        ));
+
+        // open, no request is made
+        match client.firehose().await {
+            Err(RetryError::CircuitOpen { retry_in }) => assert_eq!(retry_in, cooldown),
+            other => panic!("expected the breaker to be open, got {:?}", other),
+        }
+        assert_eq!(client.inner().calls(), 2);
+
+        // half open, one request is let through and its failure opens the breaker again
+        tokio::time::advance(cooldown).await;
+        assert!(matches!(
+            client.firehose().await,
+            Err(RetryError::Client(_))
+        ));
+        assert!(matches!(
+            client.firehose().await,
+            Err(RetryError::CircuitOpen { .. })
+        ));
+        assert_eq!(client.inner().calls(), 3);
+
+        // a success closes it
+        tokio::time::advance(cooldown).await;
+        assert!(client.firehose().await.is_ok());
+        assert!(client.firehose().await.is_ok());
+        assert_eq!(client.inner().calls(), 5);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn non_retryable_errors_dont_trip_the_breaker() {
+        let client = RetryClient::new(TestClient::failing([
+            NOT_RETRYABLE,
+            NOT_RETRYABLE,
+            NOT_RETRYABLE,
+        ]))
+        .circuit_breaker(2, Duration::from_secs(60));
+
+        for _ in 0..3 {
+            assert!(matches!(
+                client.firehose().await,
+                Err(RetryError::Client(_))
+            ));
+        }
+        assert!(client.firehose().await.is_ok());
+        assert_eq!(client.inner().calls(), 4);
+    }
+}
@@ -0,0 +1,536 @@
+use super::uniswap::{response_types::OrderResponseInner, ClientError, HashMismatchPolicy};
+use futures::{stream::BoxStream, StreamExt};
+use reqwest::{
+    header::{ACCEPT, CACHE_CONTROL},
+    Client as ReqwestClient, StatusCode, Url,
+};
+use serde::Deserialize;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::{self, Message};
+use uniswapx_sdk_core::order::SignedOrder;
+
+#[allow(unused_imports)]
+use tracing::{debug, error, info, trace, warn};
+
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// the `Last-Event-ID` header, sent when resuming a server sent events stream
+const LAST_EVENT_ID: &str = "last-event-id";
+
+/// a push based client over a websocket or server sent events endpoint
+///
+/// each message is either a single order or a page of orders, in the same shape as the rest api,
+/// see [OrderResponseInner] and [crate::uniswap::response_types::OrderResponse]
+///
+/// the connection is re-established with backoff whenever it drops, resuming from the last cursor seen,
+/// which is sent as the `cursor` query param and, for server sent events, the `Last-Event-ID` header
+///
+/// ```ignore
+/// let stream = StreamingClient::new(Url::parse("wss://example.com/orders")?).stream();
+/// let orders = PushSubscriber::new(cache, stream).subscribe();
+/// ```
+pub struct StreamingClient {
+    http: ReqwestClient,
+    url: Url,
+    transport: Transport,
+    cursor: Option<String>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    hash_mismatch: HashMismatchPolicy,
+}
+
+/// how [StreamingClient] connects to its endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    WebSocket,
+    /// server sent events, i.e. a long lived `text/event-stream` response
+    Sse,
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    WebSocket(Box<tungstenite::Error>),
+    Network(reqwest::Error),
+    /// the server responded to the event stream request with a non success status
+    Http(StatusCode),
+    Json(serde_json::Error),
+    Client(ClientError),
+}
+
+/// the data of a websocket message or server sent event, with the event id if it had one
+struct Frame {
+    data: String,
+    id: Option<String>,
+}
+
+/// an [crate::uniswap::response_types::OrderResponse] with each order left as json
+#[derive(Deserialize)]
+struct RawPage {
+    orders: Vec<serde_json::Value>,
+    cursor: Option<String>,
+}
+
+impl StreamingClient {
+    /// the transport is picked from the scheme, `ws` and `wss` are websockets, anything else is server sent events
+    pub fn new(url: Url) -> Self {
+        let transport = match url.scheme() {
+            "ws" | "wss" => Transport::WebSocket,
+            _ => Transport::Sse,
+        };
+
+        Self {
+            http: ReqwestClient::new(),
+            url,
+            transport,
+            cursor: None,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            hash_mismatch: HashMismatchPolicy::default(),
+        }
+    }
+
+    pub fn transport(mut self, transport: Transport) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// start from a cursor, e.g. one saved from a previous run
+    pub fn cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+
+    /// the reconnect backoff starts at `initial` and doubles each failed attempt, up to `max`
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// what to do with orders whose `orderHash` doesnt match the encoded order, defaults to [HashMismatchPolicy::Drop]
+    pub fn hash_mismatch(mut self, policy: HashMismatchPolicy) -> Self {
+        self.hash_mismatch = policy;
+        self
+    }
+
+    /// a never ending stream of orders, reconnecting whenever the connection drops
+    ///
+    /// orders may be repeated after a reconnect, e.g. feed this into a [crate::subscriber::push::PushSubscriber]
+    pub fn stream(mut self) -> BoxStream<'static, SignedOrder> {
+        Box::pin(async_stream::stream! {
+            let mut backoff = self.initial_backoff;
+
+            loop {
+                match self.connect().await {
+                    Ok(mut frames) => {
+                        info!("streaming client: connected to {}", self.url);
+                        backoff = self.initial_backoff;
+
+                        while let Some(frame) = frames.next().await {
+                            let frame = match frame {
+                                Ok(frame) => frame,
+                                Err(e) => {
+                                    error!("streaming client: connection error: {}", e);
+                                    break;
+                                }
+                            };
+
+                            match self.decode(frame) {
+                                Ok(orders) => {
+                                    for order in orders {
+                                        yield order;
+                                    }
+                                }
+                                Err(e) => error!("streaming client: error decoding message: {}", e),
+                            }
+                        }
+
+                        warn!("streaming client: connection closed");
+                    }
+                    Err(e) => error!("streaming client: error connecting to {}: {}", self.url, e),
+                }
+
+                debug!("streaming client: reconnecting in {:?}", backoff);
+                tokio::time::sleep(backoff).await;
+                backoff = backoff.saturating_mul(2).min(self.max_backoff);
+            }
+        })
+    }
+
+    /// decode a frame, moving the cursor forward once its decoded
+    ///
+    /// orders that cant be decoded are logged and skipped like [crate::uniswap::UniswapClient::decode_page],
+    /// so one bad order doesnt fail the frame
+    fn decode(&mut self, frame: Frame) -> Result<Vec<SignedOrder>, StreamError> {
+        let page = parse_message(&frame.data)?;
+
+        let mut orders = Vec::with_capacity(page.orders.len());
+        for value in page.orders {
+            let order_hash = value
+                .get("orderHash")
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+
+            let inner = match serde_json::from_value::<OrderResponseInner>(value) {
+                Ok(inner) => inner,
+                Err(e) => {
+                    warn!(
+                        "streaming client: cant parse order {}, skipping: {}",
+                        order_hash, e
+                    );
+                    continue;
+                }
+            };
+
+            match self.hash_mismatch.decode(inner) {
+                Ok(order) => orders.extend(order),
+                Err(ClientError::Encoding(e)) => {
+                    warn!(
+                        "streaming client: cant decode order {}, skipping: {}",
+                        order_hash, e
+                    );
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        if let Some(id) = frame.id {
+            self.cursor = Some(id);
+        }
+        if let Some(cursor) = page.cursor.filter(|cursor| !cursor.is_empty()) {
+            self.cursor = Some(cursor);
+        }
+
+        Ok(orders)
+    }
+
+    /// the url with the current cursor, replacing any cursor already in the query
+    fn resume_url(&self) -> Url {
+        let mut url = self.url.clone();
+
+        if let Some(cursor) = &self.cursor {
+            let pairs: Vec<(String, String)> = url
+                .query_pairs()
+                .filter(|(key, _)| key != "cursor")
+                .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                .collect();
+
+            url.query_pairs_mut()
+                .clear()
+                .extend_pairs(pairs)
+                .append_pair("cursor", cursor);
+        }
+
+        url
+    }
+
+    async fn connect(&self) -> Result<BoxStream<'static, Result<Frame, StreamError>>, StreamError> {
+        match self.transport {
+            Transport::WebSocket => self.connect_ws().await,
+            Transport::Sse => self.connect_sse().await,
+        }
+    }
+
+    async fn connect_ws(
+        &self,
+    ) -> Result<BoxStream<'static, Result<Frame, StreamError>>, StreamError> {
+        let (mut ws, _) = tokio_tungstenite::connect_async(self.resume_url()).await?;
+
+        Ok(Box::pin(async_stream::try_stream! {
+            while let Some(message) = ws.next().await {
+                match message? {
+                    Message::Text(data) => yield Frame { data, id: None },
+                    Message::Binary(data) => yield Frame {
+                        data: String::from_utf8_lossy(&data).into_owned(),
+                        id: None,
+                    },
+                    Message::Close(_) => break,
+                    // pings are answered by tungstenite
+                    Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => {}
+                }
+            }
+        }))
+    }
+
+    async fn connect_sse(
+        &self,
+    ) -> Result<BoxStream<'static, Result<Frame, StreamError>>, StreamError> {
+        let mut req = self
+            .http
+            .get(self.resume_url())
+            .header(ACCEPT, "text/event-stream")
+            .header(CACHE_CONTROL, "no-cache");
+
+        if let Some(cursor) = &self.cursor {
+            req = req.header(LAST_EVENT_ID, cursor);
+        }
+
+        let mut res = req.send().await?;
+        if !res.status().is_success() {
+            return Err(StreamError::Http(res.status()));
+        }
+
+        Ok(Box::pin(async_stream::try_stream! {
+            let mut parser = SseParser::default();
+
+            while let Some(chunk) = res.chunk().await? {
+                for frame in parser.feed(&chunk) {
+                    yield frame;
+                }
+            }
+        }))
+    }
+}
+
+/// a message is either a page of orders or a single order
+///
+/// not an untagged enum, serde cant buffer the `u128` in [OrderResponseInner] to try each variant
+/// a single order or a page of orders, the orders are left undecoded, see [StreamingClient::decode]
+fn parse_message(data: &str) -> Result<RawPage, serde_json::Error> {
+    let value: serde_json::Value = serde_json::from_str(data)?;
+
+    if value.get("orders").is_some() {
+        return serde_json::from_value(value);
+    }
+
+    Ok(RawPage {
+        orders: vec![value],
+        cursor: None,
+    })
+}
+
+/// an incremental parser for the `text/event-stream` format
+///
+/// see https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation
+#[derive(Default)]
+struct SseParser {
+    buf: Vec<u8>,
+    data: Vec<String>,
+    id: Option<String>,
+}
+
+impl SseParser {
+    /// feed a chunk of the body, returning any events it completed
+    fn feed(&mut self, chunk: &[u8]) -> Vec<Frame> {
+        self.buf.extend_from_slice(chunk);
+
+        let mut frames = Vec::new();
+        while let Some(end) = self.buf.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buf.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    frames.push(Frame {
+                        data: std::mem::take(&mut self.data).join("\n"),
+                        id: self.id.clone(),
+                    });
+                }
+                continue;
+            }
+
+            // lines starting with a colon are comments, e.g. keep alives
+            if line.starts_with(':') {
+                continue;
+            }
+
+            let (field, value) = match line.split_once(':') {
+                Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+                None => (line, ""),
+            };
+
+            match field {
+                "data" => self.data.push(value.to_string()),
+                "id" => self.id = Some(value.to_string()),
+                _ => trace!("streaming client: ignoring sse field {}", field),
+            }
+        }
+
+        frames
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+impl std::fmt::Display for StreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamError::WebSocket(e) => write!(f, "WebSocket error: {}", e),
+            StreamError::Network(e) => write!(f, "Network error: {}", e),
+            StreamError::Http(status) => write!(f, "Http error: {}", status),
+            StreamError::Json(e) => write!(f, "Json error: {}", e),
+            StreamError::Client(e) => write!(f, "Client error: {}", e),
+        }
+    }
+}
+
+impl From<tungstenite::Error> for StreamError {
+    fn from(e: tungstenite::Error) -> Self {
+        Self::WebSocket(Box::new(e))
+    }
+}
+
+impl From<reqwest::Error> for StreamError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Network(e)
+    }
+}
+
+impl From<serde_json::Error> for StreamError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<ClientError> for StreamError {
+    fn from(e: ClientError) -> Self {
+        Self::Client(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::api_order;
+    use alloy_primitives::U256;
+    use futures::SinkExt;
+    use tokio::{net::TcpListener, sync::mpsc};
+    use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+    use uniswapx_sdk_core::test_utils::{dutch_builder, u};
+
+    /// a page with a single dutch order, in the api shape
+    fn page(nonce: u64, cursor: &str) -> String {
+        let order = dutch_builder()
+            .nonce(u(nonce))
+            .build()
+            .unwrap()
+            .signed("0x".to_string());
+
+        serde_json::json!({ "orders": [api_order(&order)], "cursor": cursor }).to_string()
+    }
+
+    /// a websocket server that sends one page per connection and then closes it,
+    /// the query of each connection is sent to the returned channel
+    // the handshake callback has to return tungstenites `ErrorResponse`
+    #[allow(clippy::result_large_err)]
+    async fn mock_ws(pages: Vec<String>) -> (Url, mpsc::UnboundedReceiver<Option<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("ws://{}/orders", listener.local_addr().unwrap())).unwrap();
+        let (queries, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            for page in pages {
+                let (tcp, _) = listener.accept().await.unwrap();
+
+                let queries = queries.clone();
+                let mut ws = tokio_tungstenite::accept_hdr_async(
+                    tcp,
+                    move |req: &Request, res: Response| {
+                        queries.send(req.uri().query().map(str::to_string)).unwrap();
+                        Ok(res)
+                    },
+                )
+                .await
+                .unwrap();
+
+                ws.send(Message::Text(page)).await.unwrap();
+                ws.close(None).await.unwrap();
+            }
+        });
+
+        (url, rx)
+    }
+
+    #[tokio::test]
+    async fn reconnects_and_resumes_from_the_cursor() {
+        let (mut url, mut queries) = mock_ws(vec![page(1, "c1"), page(2, "c2")]).await;
+        url.set_query(Some("chainId=1&cursor=stale"));
+
+        let stream = StreamingClient::new(url)
+            .cursor("c0")
+            .backoff(Duration::from_millis(1), Duration::from_millis(10))
+            .stream();
+
+        let orders: Vec<SignedOrder> =
+            tokio::time::timeout(Duration::from_secs(5), stream.take(2).collect())
+                .await
+                .expect("two orders over two connections");
+
+        let nonces: Vec<_> = orders.iter().map(|o| o.order.info().nonce).collect();
+        assert_eq!(nonces, vec![U256::from(1), U256::from(2)]);
+
+        assert_eq!(
+            queries.recv().await.unwrap().as_deref(),
+            Some("chainId=1&cursor=c0")
+        );
+        assert_eq!(
+            queries.recv().await.unwrap().as_deref(),
+            Some("chainId=1&cursor=c1")
+        );
+    }
+
+    #[test]
+    fn skips_bad_orders_in_a_frame() {
+        let order = |nonce| {
+            api_order(
+                &dutch_builder()
+                    .nonce(u(nonce))
+                    .build()
+                    .unwrap()
+                    .signed("0x".to_string()),
+            )
+        };
+
+        let mut not_an_order = order(2);
+        not_an_order["encodedOrder"] = "0x1234".into();
+        let mut missing_fields = order(3);
+        missing_fields.as_object_mut().unwrap().remove("signature");
+
+        let mut client = StreamingClient::new(Url::parse("wss://example.com/orders").unwrap());
+        let orders = client
+            .decode(Frame {
+                data: serde_json::json!({
+                    "orders": [not_an_order, order(1), missing_fields],
+                    "cursor": "c1",
+                })
+                .to_string(),
+                id: None,
+            })
+            .unwrap();
+
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].order.info().nonce, U256::from(1));
+        assert_eq!(client.cursor.as_deref(), Some("c1"));
+    }
+
+    #[test]
+    fn bad_frames_dont_move_the_cursor() {
+        let mut client =
+            StreamingClient::new(Url::parse("wss://example.com/orders").unwrap()).cursor("c0");
+
+        for data in ["not json", r#"{ "orders": 1, "cursor": "c1" }"#] {
+            let frame = Frame {
+                data: data.to_string(),
+                id: Some("c1".to_string()),
+            };
+
+            assert!(matches!(client.decode(frame), Err(StreamError::Json(_))));
+            assert_eq!(client.cursor.as_deref(), Some("c0"));
+        }
+    }
+
+    #[test]
+    fn parses_sse_events_across_chunks() {
+        let mut parser = SseParser::default();
+
+        assert!(parser.feed(b": keep alive\nid: c1\nda").is_empty());
+        let frames = parser.feed(b"ta: {\"a\":\ndata: 1}\r\n\r\n");
+
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].data, "{\"a\":\n1}");
+        assert_eq!(frames[0].id.as_deref(), Some("c1"));
+    }
+}
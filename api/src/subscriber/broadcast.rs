@@ -0,0 +1,174 @@
+use futures::{stream::BoxStream, StreamExt};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+use tokio::sync::broadcast;
+use uniswapx_sdk_core::utils::spawn_with_shutdown;
+
+#[allow(unused_imports)]
+use tracing::{debug, error, info, trace, warn};
+
+/// one of many consumers of a subscription, see [super::Subscriber::subscribe_broadcast]
+///
+/// a receiver gets the targets sent after it was created as long as it keeps up,
+/// one that falls more than the channel capacity behind skips the oldest targets and is told how many it missed,
+/// so a slow receiver doesnt see every target
+///
+/// note: skipped targets are lost, theyre never resent, and the subscribers have already cached the skipped orders
+/// so they wont be emitted again either, a lagging consumer that needs them should resync from the [uniswapx_sdk_core::utils::OrderCache]
+///
+/// cloning a receiver creates a new receiver that starts from the next target sent,
+/// it doesnt get the targets still queued for the receiver it was cloned from
+pub struct BroadcastReceiver<T> {
+    rx: broadcast::Receiver<T>,
+    missed: Arc<AtomicU64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BroadcastError {
+    /// the receiver fell behind and skipped this many targets, the next call to recv continues from the oldest kept target
+    ///
+    /// the skipped targets are lost
+    Lagged(u64),
+    /// the subscription ended
+    Closed,
+}
+
+impl<T: Clone + Send + 'static> BroadcastReceiver<T> {
+    /// spawn a task that forwards `stream` to the receivers, it stops once every receiver is dropped
+    pub(crate) fn spawn(stream: BoxStream<'static, T>, capacity: usize) -> Self {
+        let (tx, rx) = broadcast::channel(capacity.max(1));
+
+        spawn_with_shutdown(Self::forward(stream, tx));
+
+        Self {
+            rx,
+            missed: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    async fn forward(mut stream: BoxStream<'static, T>, tx: broadcast::Sender<T>) {
+        while let Some(target) = stream.next().await {
+            if tx.send(target).is_err() {
+                debug!("broadcast: every receiver was dropped, stopping");
+                return;
+            }
+        }
+
+        warn!("broadcast: subscription ended");
+    }
+
+    /// the next target, or how many were skipped if this receiver lagged
+    pub async fn recv(&mut self) -> Result<T, BroadcastError> {
+        match self.rx.recv().await {
+            Ok(target) => Ok(target),
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                self.missed.fetch_add(n, Ordering::Relaxed);
+                warn!("broadcast: receiver lagged, skipped {} targets", n);
+                Err(BroadcastError::Lagged(n))
+            }
+            Err(broadcast::error::RecvError::Closed) => Err(BroadcastError::Closed),
+        }
+    }
+
+    /// the total number of targets this receiver has skipped
+    pub fn missed(&self) -> u64 {
+        self.missed.load(Ordering::Relaxed)
+    }
+
+    /// the number of targets sent but not yet received by this receiver
+    pub fn len(&self) -> usize {
+        self.rx.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rx.is_empty()
+    }
+
+    /// a stream of the targets, skipped targets are lost, they are only logged and counted in [BroadcastReceiver::missed]
+    pub fn into_stream(mut self) -> BoxStream<'static, T> {
+        Box::pin(async_stream::stream! {
+            loop {
+                match self.recv().await {
+                    Ok(target) => yield target,
+                    Err(BroadcastError::Lagged(_)) => continue,
+                    Err(BroadcastError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+impl<T: Clone> Clone for BroadcastReceiver<T> {
+    fn clone(&self) -> Self {
+        Self {
+            rx: self.rx.resubscribe(),
+            missed: Arc::new(AtomicU64::new(0)),
+        }
+    }
+}
+
+impl std::error::Error for BroadcastError {}
+
+impl std::fmt::Display for BroadcastError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BroadcastError::Lagged(n) => write!(f, "Receiver lagged, skipped {}", n),
+            BroadcastError::Closed => write!(f, "Subscription closed"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// a receiver of 2 that was sent 0..5 and closed before it read anything
+    async fn lagged() -> BroadcastReceiver<u64> {
+        let rx = BroadcastReceiver::spawn(futures::stream::iter(0..5).boxed(), 2);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        rx
+    }
+
+    #[tokio::test]
+    async fn lagged_receivers_skip_the_oldest_targets() {
+        let mut rx = lagged().await;
+        assert_eq!(rx.len(), 5);
+
+        assert_eq!(rx.recv().await, Err(BroadcastError::Lagged(3)));
+        assert_eq!(rx.missed(), 3);
+
+        assert_eq!(rx.recv().await, Ok(3));
+        assert_eq!(rx.recv().await, Ok(4));
+        assert_eq!(rx.recv().await, Err(BroadcastError::Closed));
+        assert_eq!(rx.missed(), 3);
+    }
+
+    #[tokio::test]
+    async fn streams_skip_lagged_targets() {
+        let rx = lagged().await;
+        let targets: Vec<_> = rx.into_stream().collect().await;
+        assert_eq!(targets, vec![3, 4]);
+    }
+
+    #[tokio::test]
+    async fn receivers_lag_on_their_own() {
+        let rx = BroadcastReceiver::spawn(
+            futures::stream::iter(0..5)
+                .then(|n| async move {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    n
+                })
+                .boxed(),
+            2,
+        );
+        let mut slow = rx.clone();
+        let fast: Vec<_> = rx.into_stream().collect().await;
+
+        assert_eq!(fast, vec![0, 1, 2, 3, 4]);
+        assert_eq!(slow.recv().await, Err(BroadcastError::Lagged(3)));
+        assert_eq!(slow.missed(), 3);
+    }
+}
@@ -0,0 +1,259 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+use tokio::sync::Notify;
+use uniswapx_sdk_core::order::SignedOrder;
+
+#[allow(unused_imports)]
+use tracing::{debug, error, info, trace, warn};
+
+/// what a full subscriber buffer does with a new order
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// drop the order thats been waiting the longest
+    DropOldest,
+    /// drop the new order
+    DropNewest,
+    /// wait for the consumer to read an order, the poller stops polling until then
+    #[default]
+    Block,
+    /// orders are read soonest deadline first, and the order with the latest deadline is dropped
+    Deadline,
+}
+
+/// counters for a subscriber buffer, shared with whoever wants to watch them
+#[derive(Debug, Default)]
+pub struct BufferStats {
+    depth: AtomicUsize,
+    received: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl BufferStats {
+    /// the number of orders waiting to be read
+    pub fn depth(&self) -> usize {
+        self.depth.load(Ordering::Relaxed)
+    }
+
+    /// the number of orders ever pushed into the buffer
+    pub fn received(&self) -> u64 {
+        self.received.load(Ordering::Relaxed)
+    }
+
+    /// the number of orders dropped by the [OverflowPolicy]
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// a bounded queue of orders between a single producer and a single consumer
+pub(crate) struct OrderBuffer {
+    queue: Mutex<VecDeque<SignedOrder>>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    pub(crate) stats: Arc<BufferStats>,
+    readable: Notify,
+    writable: Notify,
+}
+
+enum Pushed {
+    /// with the order dropped to make room if any
+    Queued(Option<SignedOrder>),
+    /// the buffer is full and the policy is [OverflowPolicy::Block], the order is handed back
+    Full(SignedOrder),
+}
+
+impl OrderBuffer {
+    pub(crate) fn new(capacity: usize, policy: OverflowPolicy, stats: Arc<BufferStats>) -> Self {
+        Self {
+            queue: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity: capacity.max(1),
+            policy,
+            stats,
+            readable: Notify::new(),
+            writable: Notify::new(),
+        }
+    }
+
+    /// push an order, returns the order dropped to make room if any
+    ///
+    /// with [OverflowPolicy::Block] this waits until theres room
+    pub(crate) async fn push(&self, order: SignedOrder) -> Option<SignedOrder> {
+        self.stats.received.fetch_add(1, Ordering::Relaxed);
+
+        let mut order = order;
+        loop {
+            match self.try_push(order) {
+                Pushed::Queued(dropped) => {
+                    if dropped.is_some() {
+                        self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+                    }
+
+                    self.readable.notify_one();
+                    return dropped;
+                }
+                Pushed::Full(full) => {
+                    debug!("subscriber: buffer full, waiting for the consumer");
+                    order = full;
+                    self.writable.notified().await;
+                }
+            }
+        }
+    }
+
+    fn try_push(&self, order: SignedOrder) -> Pushed {
+        let mut queue = self.queue.lock().expect("buffer lock");
+
+        let dropped = if queue.len() < self.capacity {
+            self.insert(&mut queue, order);
+            None
+        } else {
+            match self.policy {
+                OverflowPolicy::Block => return Pushed::Full(order),
+                OverflowPolicy::DropNewest => Some(order),
+                OverflowPolicy::DropOldest => {
+                    let oldest = queue.pop_front();
+                    self.insert(&mut queue, order);
+                    oldest
+                }
+                OverflowPolicy::Deadline => {
+                    self.insert(&mut queue, order);
+                    queue.pop_back()
+                }
+            }
+        };
+
+        self.stats.depth.store(queue.len(), Ordering::Relaxed);
+        Pushed::Queued(dropped)
+    }
+
+    /// waits for an order
+    pub(crate) async fn pop(&self) -> SignedOrder {
+        loop {
+            if let Some(order) = self.try_pop() {
+                self.writable.notify_one();
+                return order;
+            }
+
+            self.readable.notified().await;
+        }
+    }
+
+    fn try_pop(&self) -> Option<SignedOrder> {
+        let mut queue = self.queue.lock().expect("buffer lock");
+        let order = queue.pop_front();
+        self.stats.depth.store(queue.len(), Ordering::Relaxed);
+
+        order
+    }
+
+    fn insert(&self, queue: &mut VecDeque<SignedOrder>, order: SignedOrder) {
+        match self.policy {
+            OverflowPolicy::Deadline => {
+                let deadline = order.deadline();
+                let idx = queue.partition_point(|queued| queued.deadline() <= deadline);
+                queue.insert(idx, order);
+            }
+            _ => queue.push_back(order),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use uniswapx_sdk_core::test_utils::{dutch_builder, u};
+
+    /// orders are told apart by their nonce
+    fn order(nonce: u64, deadline: u64) -> SignedOrder {
+        dutch_builder()
+            .nonce(u(nonce))
+            .deadline(u(deadline))
+            .build()
+            .unwrap()
+            .signed("0x".to_string())
+    }
+
+    fn nonce(order: &SignedOrder) -> u64 {
+        order.order.info().nonce.to()
+    }
+
+    /// a buffer of 2 with 3 orders pushed, the second has the latest deadline and the third the soonest
+    async fn overflowed(policy: OverflowPolicy) -> (OrderBuffer, Vec<u64>) {
+        let buf = OrderBuffer::new(2, policy, Arc::default());
+
+        let mut dropped = Vec::new();
+        for (nonce, deadline) in [(1, 3000), (2, 4000), (3, 2000)] {
+            dropped.extend(
+                buf.push(order(nonce, deadline))
+                    .await
+                    .as_ref()
+                    .map(self::nonce),
+            );
+        }
+
+        (buf, dropped)
+    }
+
+    async fn drain(buf: &OrderBuffer) -> Vec<u64> {
+        let mut nonces = Vec::new();
+        while buf.stats.depth() > 0 {
+            nonces.push(nonce(&buf.pop().await));
+        }
+        nonces
+    }
+
+    #[tokio::test]
+    async fn drop_oldest() {
+        let (buf, dropped) = overflowed(OverflowPolicy::DropOldest).await;
+
+        assert_eq!(dropped, vec![1]);
+        assert_eq!((buf.stats.received(), buf.stats.dropped()), (3, 1));
+        assert_eq!(drain(&buf).await, vec![2, 3]);
+    }
+
+    #[tokio::test]
+    async fn drop_newest() {
+        let (buf, dropped) = overflowed(OverflowPolicy::DropNewest).await;
+
+        assert_eq!(dropped, vec![3]);
+        assert_eq!((buf.stats.received(), buf.stats.dropped()), (3, 1));
+        assert_eq!(drain(&buf).await, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn deadline() {
+        let (buf, dropped) = overflowed(OverflowPolicy::Deadline).await;
+
+        assert_eq!(dropped, vec![2]);
+        assert_eq!((buf.stats.received(), buf.stats.dropped()), (3, 1));
+        assert_eq!(drain(&buf).await, vec![3, 1]);
+    }
+
+    #[tokio::test]
+    async fn block() {
+        let buf = Arc::new(OrderBuffer::new(2, OverflowPolicy::Block, Arc::default()));
+        buf.push(order(1, 3000)).await;
+        buf.push(order(2, 2000)).await;
+
+        let pushing = tokio::spawn({
+            let buf = buf.clone();
+            async move { buf.push(order(3, 4000)).await }
+        });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!pushing.is_finished());
+        assert_eq!((buf.stats.received(), buf.stats.depth()), (3, 2));
+
+        assert_eq!(nonce(&buf.pop().await), 1);
+        assert!(pushing.await.unwrap().is_none());
+
+        assert_eq!(buf.stats.dropped(), 0);
+        assert_eq!(drain(&buf).await, vec![2, 3]);
+    }
+}
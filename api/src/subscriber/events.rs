@@ -0,0 +1,378 @@
+use super::Subscriber;
+use alloy_primitives::{Address, B256, U256};
+use ethers::{
+    contract::LogMeta,
+    providers::Middleware,
+    types::{Address as EthersAddress, ValueOrArray},
+};
+use futures::{stream::BoxStream, StreamExt};
+use std::{sync::Arc, time::Duration};
+use tokio::task::JoinHandle;
+use uniswapx_ethers_bindings::reactor_events::{FillFilter, ReactorEvents};
+use uniswapx_sdk_core::{
+    config::ChainConfig,
+    utils::{spawn_with_shutdown, OrderCache},
+};
+
+#[allow(unused_imports)]
+use tracing::{debug, error, info, trace, warn};
+
+/// an order filled on chain, from a reactors `Fill` event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FillEvent {
+    pub order_hash: B256,
+    pub filler: Address,
+    pub swapper: Address,
+    pub nonce: U256,
+    pub reactor: Address,
+    pub block_number: u64,
+}
+
+impl FillEvent {
+    fn new(fill: FillFilter, meta: LogMeta) -> Self {
+        Self {
+            order_hash: B256::from(fill.order_hash),
+            filler: Address::from(fill.filler.0),
+            swapper: Address::from(fill.swapper.0),
+            nonce: U256::from_limbs(fill.nonce.0),
+            reactor: Address::from(meta.address.0),
+            block_number: meta.block_number.as_u64(),
+        }
+    }
+}
+
+/// the most blocks read with a single `eth_getLogs` call, most nodes cap the range
+const MAX_BLOCK_RANGE: u64 = 1000;
+
+/// a [Subscriber] to the `Fill` events of some reactors
+///
+/// fills are read with `eth_getLogs` over block ranges, starting from the block after the head when subscribed,
+/// a failed poll is retried from the same block so no fills are missed
+///
+/// with no reactors the stream ends straight away, see [FillSubscriber::from_config]
+pub struct FillSubscriber<M> {
+    provider: Arc<M>,
+    reactors: Vec<Address>,
+    poll_interval: u64,
+}
+
+impl<M: Middleware + 'static> FillSubscriber<M> {
+    /// watch `reactors`, polling for new blocks every `poll_interval` seconds
+    pub fn new(provider: Arc<M>, reactors: Vec<Address>, poll_interval: u64) -> Self {
+        Self {
+            provider,
+            reactors,
+            poll_interval,
+        }
+    }
+
+    /// watch every reactor in `config`, some chains have none
+    pub fn from_config(provider: Arc<M>, config: &ChainConfig, poll_interval: u64) -> Self {
+        Self::new(
+            provider,
+            config.reactors.values().copied().collect(),
+            poll_interval,
+        )
+    }
+
+    /// spawn a task that removes orders from `cache` as soon as theyre filled,
+    /// the filler and block are passed to the [OrderCache::on_evict] callback
+    pub fn evict_from(self, cache: Arc<OrderCache>) -> JoinHandle<Option<()>> {
+        let mut fills = self.subscribe();
+
+        spawn_with_shutdown(async move {
+            while let Some(fill) = fills.next().await {
+                let removed = cache.lock().await.remove_filled(
+                    &fill.order_hash,
+                    fill.filler,
+                    fill.block_number,
+                );
+
+                match removed {
+                    Some(_) => info!(
+                        "fill subscriber: order {} filled by {} in block {}, evicted",
+                        fill.order_hash, fill.filler, fill.block_number
+                    ),
+                    None => trace!(
+                        "fill subscriber: order {} filled, not in cache",
+                        fill.order_hash
+                    ),
+                }
+            }
+        })
+    }
+}
+
+impl<M: Middleware + 'static> Subscriber for FillSubscriber<M> {
+    type Target = FillEvent;
+
+    fn subscribe(self) -> BoxStream<'static, FillEvent> {
+        // an empty address list doesnt restrict the logs, so every contracts `Fill` would be read
+        if self.reactors.is_empty() {
+            warn!("fill subscriber: no reactors to watch");
+            return Box::pin(futures::stream::empty());
+        }
+
+        let reactors: Vec<EthersAddress> = self
+            .reactors
+            .iter()
+            .map(|reactor| EthersAddress::from(reactor.0 .0))
+            .collect();
+        let interval = Duration::from_secs(self.poll_interval);
+
+        Box::pin(async_stream::stream! {
+            let events = ReactorEvents::new(EthersAddress::zero(), self.provider.clone());
+            // the first block that hasnt been read yet
+            let mut next: Option<u64> = None;
+
+            loop {
+                let head = match self.provider.get_block_number().await {
+                    Ok(head) => head.as_u64(),
+                    Err(e) => {
+                        error!("fill subscriber: error getting the block number: {}", e);
+                        tokio::time::sleep(interval).await;
+                        continue;
+                    }
+                };
+
+                let from = *next.get_or_insert(head + 1);
+                if from > head {
+                    tokio::time::sleep(interval).await;
+                    continue;
+                }
+
+                let to = head.min(from + MAX_BLOCK_RANGE - 1);
+                let fills = events
+                    .fill_filter()
+                    .address(ValueOrArray::Array(reactors.clone()))
+                    .from_block(from)
+                    .to_block(to)
+                    .query_with_meta()
+                    .await;
+
+                let fills = match fills {
+                    Ok(fills) => fills,
+                    Err(e) => {
+                        error!("fill subscriber: error getting fills in blocks {} to {}: {}", from, to, e);
+                        tokio::time::sleep(interval).await;
+                        continue;
+                    }
+                };
+
+                trace!("fill subscriber: {} fills in blocks {} to {}", fills.len(), from, to);
+                for (fill, meta) in fills {
+                    yield FillEvent::new(fill, meta);
+                }
+                next = Some(to + 1);
+
+                // keep going if theres more blocks to catch up on
+                if to == head {
+                    tokio::time::sleep(interval).await;
+                }
+            }
+        })
+    }
+
+    /// fills are never stale
+    fn is_expired(_: &FillEvent) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::{
+        abi::AbiEncode,
+        contract::EthEvent,
+        providers::{JsonRpcError, MockProvider, MockResponse, Provider},
+        types::{Filter, Log, H256, U64},
+    };
+    use tokio::sync::mpsc;
+    use uniswapx_sdk_core::{
+        config::ARBITRUM,
+        order::SignedOrder,
+        test_utils::{self, dutch_builder},
+        utils::EvictionReason,
+    };
+
+    const REACTOR: Address = Address::repeat_byte(0xaa);
+    const FILLER: Address = Address::repeat_byte(0xbb);
+
+    fn ethers_address(address: Address) -> EthersAddress {
+        EthersAddress::from(address.0 .0)
+    }
+
+    fn topic(address: Address) -> H256 {
+        H256::from(ethers_address(address))
+    }
+
+    fn fill_log(order_hash: B256, block_number: u64) -> Log {
+        Log {
+            address: ethers_address(REACTOR),
+            topics: vec![
+                FillFilter::signature(),
+                H256::from(order_hash.0),
+                topic(FILLER),
+                topic(test_utils::swapper()),
+            ],
+            data: ethers::types::U256::from(7).encode().into(),
+            block_hash: Some(H256::repeat_byte(1)),
+            block_number: Some(U64::from(block_number)),
+            transaction_hash: Some(H256::repeat_byte(2)),
+            transaction_index: Some(U64::zero()),
+            log_index: Some(ethers::types::U256::zero()),
+            ..Default::default()
+        }
+    }
+
+    fn fill(order_hash: B256, block_number: u64) -> FillEvent {
+        FillEvent {
+            order_hash,
+            filler: FILLER,
+            swapper: test_utils::swapper(),
+            nonce: U256::from(7),
+            reactor: REACTOR,
+            block_number,
+        }
+    }
+
+    fn error() -> MockResponse {
+        MockResponse::Error(JsonRpcError {
+            code: -32000,
+            message: "node is down".to_string(),
+            data: None,
+        })
+    }
+
+    /// the mocked provider answers with `responses` in order
+    fn mocked(responses: Vec<MockResponse>) -> (Arc<Provider<MockProvider>>, MockProvider) {
+        let (provider, mock) = Provider::mocked();
+        for response in responses.into_iter().rev() {
+            mock.push_response(response);
+        }
+
+        (Arc::new(provider), mock)
+    }
+
+    fn block_number(n: u64) -> MockResponse {
+        MockResponse::Value(serde_json::to_value(U64::from(n)).unwrap())
+    }
+
+    fn logs(logs: Vec<Log>) -> MockResponse {
+        MockResponse::Value(serde_json::to_value(logs).unwrap())
+    }
+
+    fn assert_get_logs(mock: &MockProvider, from: u64, to: u64) {
+        let filter = Filter::new()
+            .address(ValueOrArray::Array(vec![ethers_address(REACTOR)]))
+            .topic0(FillFilter::signature())
+            .from_block(from)
+            .to_block(to);
+
+        mock.assert_request("eth_getLogs", [filter]).unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn polls_from_the_last_block_read() {
+        let a = B256::repeat_byte(1);
+        let b = B256::repeat_byte(2);
+        let (provider, mock) = mocked(vec![
+            // subscribed at 10, theres no new block yet
+            block_number(10),
+            block_number(12),
+            logs(vec![fill_log(a, 12)]),
+            // failed polls are retried from the same block
+            error(),
+            block_number(14),
+            error(),
+            block_number(14),
+            logs(vec![fill_log(b, 13)]),
+        ]);
+
+        let mut fills = FillSubscriber::new(provider, vec![REACTOR], 1).subscribe();
+        assert_eq!(fills.next().await, Some(fill(a, 12)));
+        assert_eq!(fills.next().await, Some(fill(b, 13)));
+
+        mock.assert_request("eth_blockNumber", ()).unwrap();
+        mock.assert_request("eth_blockNumber", ()).unwrap();
+        assert_get_logs(&mock, 11, 12);
+        mock.assert_request("eth_blockNumber", ()).unwrap();
+        mock.assert_request("eth_blockNumber", ()).unwrap();
+        assert_get_logs(&mock, 13, 14);
+        mock.assert_request("eth_blockNumber", ()).unwrap();
+        assert_get_logs(&mock, 13, 14);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn catches_up_a_range_at_a_time() {
+        let a = B256::repeat_byte(1);
+        let (provider, mock) = mocked(vec![
+            block_number(10),
+            block_number(2100),
+            logs(vec![]),
+            block_number(2100),
+            logs(vec![]),
+            block_number(2100),
+            logs(vec![fill_log(a, 2050)]),
+        ]);
+
+        let mut fills = FillSubscriber::new(provider, vec![REACTOR], 1).subscribe();
+        assert_eq!(fills.next().await, Some(fill(a, 2050)));
+
+        mock.assert_request("eth_blockNumber", ()).unwrap();
+        for (from, to) in [(11, 1010), (1011, 2010), (2011, 2100)] {
+            mock.assert_request("eth_blockNumber", ()).unwrap();
+            assert_get_logs(&mock, from, to);
+        }
+    }
+
+    #[tokio::test]
+    async fn no_reactors_is_an_empty_stream() {
+        let (provider, mock) = mocked(vec![]);
+        let config = ChainConfig::builtin(ARBITRUM).unwrap();
+        assert!(config.reactors.is_empty());
+
+        let mut fills = FillSubscriber::from_config(provider, &config, 1).subscribe();
+        assert_eq!(fills.next().await, None);
+        assert!(mock.assert_request("eth_blockNumber", ()).is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn evicts_filled_orders() {
+        let order: SignedOrder = dutch_builder()
+            .deadline(U256::from(u64::MAX))
+            .build()
+            .unwrap()
+            .signed("0x".to_string());
+        let hash = order.order_hash();
+
+        let (cache_provider, _) = mocked(vec![]);
+        let cache = OrderCache::new(cache_provider, test_utils::config(), 3600);
+        assert!(cache.lock().await.insert(order));
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        cache
+            .on_evict(move |order, reason| {
+                if let EvictionReason::Filled {
+                    filler,
+                    block_number,
+                } = reason
+                {
+                    tx.send((order.order_hash(), *filler, *block_number))
+                        .unwrap();
+                }
+            })
+            .await;
+
+        let (provider, _mock) = mocked(vec![
+            block_number(10),
+            block_number(11),
+            logs(vec![fill_log(hash, 11)]),
+        ]);
+        FillSubscriber::new(provider, vec![REACTOR], 1).evict_from(cache.clone());
+
+        assert_eq!(rx.recv().await, Some((hash, FILLER, 11)));
+        assert!(cache.lock().await.is_tombstoned(&hash));
+    }
+}
@@ -0,0 +1,52 @@
+/// [OrderSubscriber], polls a [crate::client::Client]
+pub mod polling;
+
+/// [push::PushSubscriber], wraps a stream of orders pushed to us
+pub mod push;
+
+/// [events::FillSubscriber], watches the reactors for fills
+pub mod events;
+
+/// the bounded buffer behind [OrderSubscriber], see [buffer::OverflowPolicy]
+pub mod buffer;
+
+/// [broadcast::BroadcastReceiver], many consumers of one subscription
+pub mod broadcast;
+
+pub use polling::OrderSubscriber;
+
+use broadcast::BroadcastReceiver;
+use futures::stream::BoxStream;
+use uniswapx_sdk_core::{order::SignedOrder, utils::OrderCache};
+
+/// an ingestion strategy, consumers only need to read the stream returned by [Subscriber::subscribe]
+pub trait Subscriber {
+    type Target;
+
+    /// start the subscription, this stream is never ending unless shutdown
+    fn subscribe(self) -> BoxStream<'static, Self::Target>;
+
+    /// if a target is no longer useful and can be dropped
+    fn is_expired(target: &Self::Target) -> bool;
+
+    /// start the subscription and share it, each clone of the receiver gets the targets sent after it was cloned
+    ///
+    /// each receiver can fall `capacity` targets behind before it starts skipping them, so a slow receiver doesnt get
+    /// every target, skipped targets are lost, see [BroadcastReceiver]
+    fn subscribe_broadcast(self, capacity: usize) -> BroadcastReceiver<Self::Target>
+    where
+        Self: Sized,
+        Self::Target: Clone + Send + 'static,
+    {
+        BroadcastReceiver::spawn(self.subscribe(), capacity)
+    }
+}
+
+/// insert an order into the cache, returns false if it was already there,
+/// or was evicted for good, e.g. filled or expired, see [uniswapx_sdk_core::utils::CachedOrders::is_tombstoned]
+pub(crate) async fn cache_new(cache: &OrderCache, order: &SignedOrder) -> bool {
+    let hash = order.order_hash();
+    let mut cache = cache.lock().await;
+
+    !cache.contains_key(&hash) && !cache.is_tombstoned(&hash) && cache.insert(order.clone())
+}
@@ -0,0 +1,166 @@
+use super::{
+    buffer::{BufferStats, OrderBuffer, OverflowPolicy},
+    cache_new, Subscriber,
+};
+use crate::client::Client;
+use futures::stream::BoxStream;
+use std::sync::Arc;
+use uniswapx_sdk_core::utils::{
+    run_with_shutdown, spawn_with_shutdown, unix_timestamp, OrderCache,
+};
+use uniswapx_sdk_core::{filter::OrderFilter, order::SignedOrder};
+
+#[allow(unused_imports)]
+use tracing::{debug, error, info, trace, warn};
+
+const DEFAULT_BUFFER_CAPACITY: usize = 1024;
+
+/// a [Subscriber] that polls a [Client] every `poll_interval` seconds
+///
+/// new orders wait in a bounded buffer until theyre read, see [OrderSubscriber::buffer]
+///
+/// see [OrderSubscriber::subscribe]
+pub struct OrderSubscriber<C> {
+    cache: Arc<OrderCache>,
+    client: C,
+    poll_interval: u64,
+    capacity: usize,
+    policy: OverflowPolicy,
+    stats: Arc<BufferStats>,
+    filter: OrderFilter,
+}
+
+impl<C> Subscriber for OrderSubscriber<C>
+where
+    C: Client<SignedOrder> + 'static,
+{
+    type Target = SignedOrder;
+
+    /// a never ending subscription to some [SignedOrder]s
+    ///
+    /// this stream can return invalid orders, consumers are expected to validate ([SignedOrder::validate_ethers]) them before use as they can expire at anytime
+    fn subscribe(self) -> BoxStream<'static, SignedOrder> {
+        let buf = Arc::new(OrderBuffer::new(self.capacity, self.policy, self.stats));
+        let client = Arc::new(self.client);
+
+        // spawn a task that dumps unseen orders into the buffer
+        spawn_with_shutdown(Self::fill_buf(
+            buf.clone(),
+            self.cache.clone(),
+            client,
+            self.filter,
+            self.poll_interval,
+        ));
+
+        Box::pin(async_stream::stream! {
+            while let Some(order) = run_with_shutdown(Self::read_buf(buf.clone())).await {
+                yield order;
+            }
+        })
+    }
+
+    fn is_expired(order: &SignedOrder) -> bool {
+        order.deadline() < unix_timestamp()
+    }
+}
+
+impl<C> OrderSubscriber<C>
+where
+    C: Client<SignedOrder> + 'static,
+{
+    pub fn new(cache: Arc<OrderCache>, client: C, poll_interval: u64) -> Self {
+        Self {
+            cache,
+            client,
+            poll_interval,
+            capacity: DEFAULT_BUFFER_CAPACITY,
+            policy: OverflowPolicy::default(),
+            stats: Arc::new(BufferStats::default()),
+            filter: OrderFilter::default(),
+        }
+    }
+
+    /// only orders matching `filter` are cached and buffered, the rest are dropped
+    pub fn filter(mut self, filter: OrderFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// hold at most `capacity` unread orders, when the buffer is full new orders are handled by `policy`
+    ///
+    /// dropped orders are removed from the cache so a later poll can return them again,
+    /// defaults to 1024 orders and [OverflowPolicy::Block]
+    pub fn buffer(mut self, capacity: usize, policy: OverflowPolicy) -> Self {
+        self.capacity = capacity;
+        self.policy = policy;
+        self
+    }
+
+    /// the buffer depth and drop counters, these keep updating after [OrderSubscriber::subscribe]
+    pub fn stats(&self) -> Arc<BufferStats> {
+        self.stats.clone()
+    }
+
+    // inline async blocks dont seem to work in a stream! macro so we need this function
+    async fn read_buf(buf: Arc<OrderBuffer>) -> SignedOrder {
+        buf.pop().await
+    }
+
+    // hits the api and condintally pushes orders into the buffer
+    // if the client returns expired orders they will be pushed into the buffer
+    async fn fill_buf(
+        buf: Arc<OrderBuffer>,
+        cache: Arc<OrderCache>,
+        client: Arc<C>,
+        filter: OrderFilter,
+        poll_interval: u64,
+    ) {
+        loop {
+            let orders = client.firehose().await;
+
+            // will try again so just continue
+            if orders.is_err() {
+                error!("subscriber: error getting order froms the client, trying again");
+                tokio::time::sleep(std::time::Duration::from_secs(poll_interval)).await;
+                continue;
+            }
+
+            let orders = orders.unwrap();
+
+            if orders.is_empty() {
+                trace!("subscriber: no orders, polling again in {}s", poll_interval);
+                tokio::time::sleep(std::time::Duration::from_secs(poll_interval)).await;
+                continue;
+            }
+
+            info!(
+                "subsciber got orders: {:?}, buf size: {:?}",
+                orders.len(),
+                buf.stats.depth()
+            );
+
+            for order in orders {
+                if !filter.matches(&order) {
+                    trace!("subscriber: order {} filtered out", order.order_hash());
+                    continue;
+                }
+
+                if !cache_new(&cache, &order).await {
+                    info!("subscriber: order already in cache");
+                    continue;
+                }
+
+                // the cache lock isnt held here, this can wait on the consumer
+                if let Some(dropped) = buf.push(order).await {
+                    warn!(
+                        "subscriber: buffer full, dropped order {}",
+                        dropped.order_hash()
+                    );
+                    cache.lock().await.remove(&dropped.order_hash());
+                }
+            }
+
+            tokio::time::sleep(std::time::Duration::from_secs(poll_interval)).await;
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use super::{cache_new, Subscriber};
+use futures::{stream::BoxStream, Stream, StreamExt};
+use std::sync::Arc;
+use uniswapx_sdk_core::{
+    filter::OrderFilter,
+    order::SignedOrder,
+    utils::{unix_timestamp, OrderCache},
+};
+
+#[allow(unused_imports)]
+use tracing::{debug, error, info, trace, warn};
+
+/// a [Subscriber] over orders pushed to us, e.g. from a websocket, SSE or webhook feed
+///
+/// orders already in the cache, or that dont match the filter, are skipped
+pub struct PushSubscriber<S> {
+    cache: Arc<OrderCache>,
+    source: S,
+    filter: OrderFilter,
+}
+
+impl<S> PushSubscriber<S>
+where
+    S: Stream<Item = SignedOrder> + Send + 'static,
+{
+    pub fn new(cache: Arc<OrderCache>, source: S) -> Self {
+        Self {
+            cache,
+            source,
+            filter: OrderFilter::default(),
+        }
+    }
+
+    /// only orders matching `filter` are cached and yielded, the rest are dropped
+    pub fn filter(mut self, filter: OrderFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+impl<S> Subscriber for PushSubscriber<S>
+where
+    S: Stream<Item = SignedOrder> + Send + 'static,
+{
+    type Target = SignedOrder;
+
+    fn subscribe(self) -> BoxStream<'static, SignedOrder> {
+        let cache = self.cache;
+        let filter = self.filter;
+        let mut source = Box::pin(self.source);
+
+        Box::pin(async_stream::stream! {
+            while let Some(order) = source.next().await {
+                if !filter.matches(&order) {
+                    trace!("push subscriber: order {} filtered out", order.order_hash());
+                    continue;
+                }
+
+                if cache_new(&cache, &order).await {
+                    yield order;
+                } else {
+                    debug!("push subscriber: order already in cache");
+                }
+            }
+
+            warn!("push subscriber: source ended");
+        })
+    }
+
+    fn is_expired(order: &SignedOrder) -> bool {
+        order.deadline() < unix_timestamp()
+    }
+}
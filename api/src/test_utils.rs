@@ -0,0 +1,10 @@
+use uniswapx_sdk_core::{config::MAINNET, order::SignedOrder};
+
+/// an open mainnet order in the shape of [crate::uniswap::response_types::OrderResponseInner]
+pub(crate) fn api_order(order: &SignedOrder) -> serde_json::Value {
+    let mut inner = serde_json::to_value(order.to_api(MAINNET)).expect("order to serialize");
+    inner["orderStatus"] = "open".into();
+    inner["createdAt"] = 0.into();
+
+    inner
+}
@@ -1,16 +1,23 @@
 /// the default response types from the uniswap api
 pub mod response_types;
 
-use super::client::Client;
-use alloy_sol_types::Error as AlloySolTypeError;
-use reqwest::{Client as ReqwestClient, Url};
+/// the query params of the uniswap api, see [params::ApiParams]
+pub mod params;
+
+use super::{
+    client::Client,
+    retry::{RetryError, RetryPolicy, RetryableError},
+};
+use alloy_primitives::B256;
+use futures::{Stream, TryStreamExt};
+use params::ApiParams;
+use reqwest::{header::RETRY_AFTER, Client as ReqwestClient, Response, StatusCode, Url};
 use response_types::{OrderResponse, OrderResponseInner, OrderStatus};
-use uniswapx_sdk_core::order::{OrderType, SignedOrder};
+use std::time::{Duration, SystemTime};
 use uniswapx_sdk_core::{
-    contracts::internal::{
-        dutch::DutchOrder, exclusive_dutch::ExclusiveDutchOrder, limit::LimitOrder,
-    },
-    order::Order,
+    config::ChainConfig,
+    encoding::{detect_order, EncodingError},
+    order::{OrderMetadata, SignedOrder},
 };
 
 #[allow(unused_imports)]
@@ -18,53 +25,237 @@ use tracing::{debug, error, info, trace, warn};
 
 const URL: &str = "https://api.uniswap.org/v2/orders";
 
+/// the [OrderMetadata::source] of orders from the uniswap api
+pub const SOURCE: &str = "uniswap";
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+const DEFAULT_MAX_PAGES: usize = 20;
+
 pub struct UniswapClient {
     client: ReqwestClient,
     url: Url,
     chain_id: usize,
+    page_size: usize,
+    max_pages: usize,
+    hash_mismatch: HashMismatchPolicy,
+    config: Option<ChainConfig>,
+    retry: Option<RetryPolicy>,
 }
 
-#[derive(Debug)]
-pub enum ClientError {
-    Network(reqwest::Error),
-    Encoding(AlloySolTypeError),
+/// what [UniswapClient] does with an order whose `orderHash` isnt the locally computed order hash
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HashMismatchPolicy {
+    /// log a warning and keep the order
+    Warn,
+    /// log a warning and skip the order
+    #[default]
+    Drop,
+    /// fail the whole request with [ClientError::HashMismatch]
+    Error,
 }
 
-pub struct ApiParams {
-    pub limit: usize,
-    pub chain_id: usize,
-    pub order_status: OrderStatus,
-}
+impl HashMismatchPolicy {
+    /// decode an order, checking its `orderHash` against the locally computed order hash
+    ///
+    /// the order type is detected with the built in reactors for the orders chain, see [decode_order]
+    ///
+    /// `None` if the order was dropped
+    pub fn decode(self, inner: OrderResponseInner) -> Result<Option<SignedOrder>, ClientError> {
+        let config = ChainConfig::builtin(inner.chain_id as u64);
+        self.decode_inner(inner, config.as_ref())
+    }
+
+    /// like [HashMismatchPolicy::decode], but detects the order type with the reactors in `config`
+    pub fn decode_with(
+        self,
+        inner: OrderResponseInner,
+        config: &ChainConfig,
+    ) -> Result<Option<SignedOrder>, ClientError> {
+        self.decode_inner(inner, Some(config))
+    }
+
+    fn decode_inner(
+        self,
+        inner: OrderResponseInner,
+        config: Option<&ChainConfig>,
+    ) -> Result<Option<SignedOrder>, ClientError> {
+        let api_hash = inner.order_hash.clone();
+        let order = decode_order(inner, config)?;
+        let computed = order.order_hash();
+
+        if api_hash.parse::<B256>().ok() != Some(computed) {
+            match self {
+                HashMismatchPolicy::Warn => {
+                    warn!("api order hash {} doesnt match {}", api_hash, computed);
+                }
+                HashMismatchPolicy::Drop => {
+                    warn!(
+                        "api order hash {} doesnt match {}, dropping",
+                        api_hash, computed
+                    );
+                    return Ok(None);
+                }
+                HashMismatchPolicy::Error => {
+                    return Err(ClientError::HashMismatch {
+                        api: api_hash,
+                        computed,
+                    });
+                }
+            }
+        }
 
-impl ApiParams {
-    fn as_query_string(&self) -> String {
-        todo!()
+        Ok(Some(order))
     }
 }
 
+#[derive(Debug)]
+pub enum ClientError {
+    Network(reqwest::Error),
+    /// the api responded with a non success status
+    Http {
+        status: StatusCode,
+        retry_after: Option<Duration>,
+    },
+    Encoding(EncodingError),
+    /// the api `orderHash` doesnt match the encoded order
+    HashMismatch {
+        api: String,
+        computed: B256,
+    },
+    /// the [RetryPolicy] circuit breaker is open, no request was made
+    CircuitOpen {
+        retry_in: Duration,
+    },
+}
+
 impl UniswapClient {
     pub fn new(chain_id: usize) -> Self {
         Self {
             client: ReqwestClient::new(),
             url: Url::parse(URL).expect("URL to parse"),
             chain_id,
+            page_size: DEFAULT_PAGE_SIZE,
+            max_pages: DEFAULT_MAX_PAGES,
+            hash_mismatch: HashMismatchPolicy::default(),
+            config: None,
+            retry: None,
+        }
+    }
+
+    /// what to do with orders whose `orderHash` doesnt match the encoded order, defaults to [HashMismatchPolicy::Drop]
+    pub fn hash_mismatch(mut self, policy: HashMismatchPolicy) -> Self {
+        self.hash_mismatch = policy;
+        self
+    }
+
+    /// the deployments used to detect order types, e.g. with overrides, defaults to [ChainConfig::builtin]
+    pub fn chain_config(mut self, config: ChainConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// retry and rate limit each page request with `policy`, so a failed page is retried on its own
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// decode a page of orders, checking each `orderHash` against the locally computed order hash
+    ///
+    /// orders that cant be decoded are logged and skipped, so one bad order doesnt fail the page
+    pub fn decode_page(&self, page: OrderResponse) -> Result<Vec<SignedOrder>, ClientError> {
+        let mut orders = Vec::with_capacity(page.orders.len());
+
+        for inner in page.orders {
+            let order_hash = inner.order_hash.clone();
+
+            let decoded = match &self.config {
+                Some(config) => self.hash_mismatch.decode_with(inner, config),
+                None => self.hash_mismatch.decode(inner),
+            };
+
+            match decoded {
+                Ok(order) => orders.extend(order),
+                Err(ClientError::Encoding(e)) => {
+                    warn!("cant decode api order {}, skipping: {}", order_hash, e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(orders)
+    }
+
+    /// use a different orders endpoint, e.g. a mirror or a local mock server
+    pub fn url(mut self, url: Url) -> Self {
+        self.url = url;
+        self
+    }
+
+    /// the `limit` sent with each page of [UniswapClient::firehose]
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// the most pages [UniswapClient::firehose] will fetch before returning
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages;
+        self
+    }
+
+    /// a stream of pages, following the cursor of each response until theres no cursor or `max_pages` pages have been fetched
+    ///
+    /// a cursor set in `params` is used as the starting point
+    pub fn paginate(
+        &self,
+        params: ApiParams,
+        max_pages: usize,
+    ) -> impl Stream<Item = Result<OrderResponse, ClientError>> + '_ {
+        async_stream::try_stream! {
+            let mut params = params;
+
+            for _ in 0..max_pages {
+                let page = self.get_orders_with_params(params.clone()).await?;
+                let cursor = page.cursor.clone().filter(|cursor| !cursor.is_empty());
+
+                yield page;
+
+                match cursor {
+                    Some(cursor) => params.cursor = Some(cursor),
+                    None => break,
+                }
+            }
         }
     }
 
-    /// todo! there are more params on thier server
+    /// fetch a single page, with the [UniswapClient::retry] policy if theres one
     pub async fn get_orders_with_params(
         &self,
         params: ApiParams,
-    ) -> Result<OrderResponse, reqwest::Error> {
-        Ok(self
+    ) -> Result<OrderResponse, ClientError> {
+        match &self.retry {
+            Some(policy) => Ok(policy.call(|| self.request_orders(&params)).await?),
+            None => self.request_orders(&params).await,
+        }
+    }
+
+    async fn request_orders(&self, params: &ApiParams) -> Result<OrderResponse, ClientError> {
+        let res = self
             .client
             .get(self.url.clone())
-            .query(&[("limit", params.limit), ("chainId", params.chain_id)])
-            .query(&[("orderStatus", params.order_status)]) // types
+            .query(params)
             .send()
-            .await?
-            .json::<OrderResponse>()
-            .await?)
+            .await?;
+
+        if !res.status().is_success() {
+            return Err(ClientError::Http {
+                status: res.status(),
+                retry_after: retry_after(&res),
+            });
+        }
+
+        Ok(res.json::<OrderResponse>().await?)
     }
 }
 
@@ -72,63 +263,128 @@ impl UniswapClient {
 impl Client<SignedOrder> for UniswapClient {
     type ClientError = ClientError;
 
-    /// returns as many open orders as possible
+    /// returns as many open orders as possible, walking the cursor up to `max_pages` pages
     async fn firehose(&self) -> Result<Vec<SignedOrder>, Self::ClientError> {
-        let res = self
-            .get_orders_with_params(ApiParams {
-                limit: 10,
-                chain_id: self.chain_id,
-                order_status: OrderStatus::Open,
-            })
-            .await?;
+        let params = ApiParams::new(self.chain_id)
+            .limit(self.page_size)
+            .order_status(OrderStatus::Open);
+
+        let pages: Vec<OrderResponse> = self.paginate(params, self.max_pages).try_collect().await?;
 
-        Ok(Vec::<SignedOrder>::try_from(res)?)
+        let mut orders = Vec::new();
+        for page in pages {
+            orders.extend(self.decode_page(page)?);
+        }
+
+        Ok(orders)
     }
 }
 
-// todo! uniswap api labels exlusive dutch as a dutch
+/// decode an api order, the api labels exclusive dutch orders as dutch,
+/// so the order type is detected from the encoded order and the reactors in `config`, see [detect_order]
+///
+/// note: this doesnt check the `orderHash`, see [HashMismatchPolicy::decode]
+pub fn decode_order(
+    order: OrderResponseInner,
+    config: Option<&ChainConfig>,
+) -> Result<SignedOrder, EncodingError> {
+    let detected = detect_order(&order.encoded_order, config, order.order_hash.parse().ok())?;
+
+    if order
+        .order_type
+        .is_some_and(|labeled| labeled != detected.order_type())
+    {
+        debug!(
+            "api labeled order {} as {:?}, detected {:?}",
+            order.order_hash,
+            order.order_type,
+            detected.order_type()
+        );
+    }
+
+    let metadata = OrderMetadata {
+        source: Some(SOURCE.to_string()),
+        received_at: Some(SystemTime::now()),
+        created_at: u64::try_from(order.created_at).ok(),
+        status: Some(order.order_status.as_str().to_string()),
+        chain_id: Some(order.chain_id as u64),
+        order_hash: Some(order.order_hash),
+        extra: order.extra,
+    };
+
+    Ok(detected.signed(order.signature).with_metadata(metadata))
+}
+
+/// see [decode_order], with the built in reactors for the orders chain
 impl TryFrom<OrderResponseInner> for SignedOrder {
-    type Error = AlloySolTypeError;
+    type Error = EncodingError;
 
     fn try_from(order: OrderResponseInner) -> Result<Self, Self::Error> {
-        // clone because were gonna consumer order
-        let sig = order.signature.clone();
-
-        let order = match order.order_type {
-            OrderType::Dutch => {
-                warn!("uniswap api returned a dutch order, but it should be really an exclusive dutch order");
-                Order::from(ExclusiveDutchOrder::try_from(order.encoded_order)?)
-                // see todo
-            }
-            OrderType::Limit => Order::from(LimitOrder::try_from(order.encoded_order)?),
-            OrderType::ExclusiveDutch => {
-                Order::from(ExclusiveDutchOrder::try_from(order.encoded_order)?)
-            }
-        };
-
-        Ok(order.signed(sig))
+        let config = ChainConfig::builtin(order.chain_id as u64);
+        decode_order(order, config.as_ref())
     }
 }
 
+/// note: this doesnt check the `orderHash` of each order, see [UniswapClient::decode_page]
 impl TryFrom<OrderResponse> for Vec<SignedOrder> {
-    type Error = AlloySolTypeError;
+    type Error = EncodingError;
 
     fn try_from(response: OrderResponse) -> Result<Self, Self::Error> {
         response
             .orders
             .into_iter()
-            .map(|order| SignedOrder::try_from(order))
+            .map(SignedOrder::try_from)
             .collect()
     }
 }
 
+/// the `Retry-After` header in seconds, http dates arent supported
+fn retry_after(res: &Response) -> Option<Duration> {
+    res.headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+impl RetryableError for ClientError {
+    fn is_retryable(&self) -> bool {
+        match self {
+            ClientError::Network(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            ClientError::Http { status, .. } => {
+                *status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+            }
+            ClientError::Encoding(_)
+            | ClientError::HashMismatch { .. }
+            | ClientError::CircuitOpen { .. } => false,
+        }
+    }
+
+    fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ClientError::Http { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
 impl std::error::Error for ClientError {}
 
 impl std::fmt::Display for ClientError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ClientError::Network(e) => write!(f, "Network error: {}", e),
+            ClientError::Http { status, .. } => write!(f, "Http error: {}", status),
             ClientError::Encoding(e) => write!(f, "Encoding error: {}", e),
+            ClientError::HashMismatch { api, computed } => {
+                write!(f, "Hash mismatch: api {} computed {}", api, computed)
+            }
+            ClientError::CircuitOpen { retry_in } => {
+                write!(f, "Circuit open, retry in {:?}", retry_in)
+            }
         }
     }
 }
@@ -139,8 +395,132 @@ impl From<reqwest::Error> for ClientError {
     }
 }
 
-impl From<AlloySolTypeError> for ClientError {
-    fn from(e: AlloySolTypeError) -> Self {
+impl From<RetryError<ClientError>> for ClientError {
+    fn from(e: RetryError<ClientError>) -> Self {
+        match e {
+            RetryError::Client(e) => e,
+            RetryError::CircuitOpen { retry_in } => Self::CircuitOpen { retry_in },
+        }
+    }
+}
+
+impl From<EncodingError> for ClientError {
+    fn from(e: EncodingError) -> Self {
         Self::Encoding(e)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::{
+        service::{make_service_fn, service_fn},
+        Body, Request, Response as HyperResponse, Server,
+    };
+    use std::{
+        convert::Infallible,
+        net::SocketAddr,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    /// serves 3 empty pages linked by cursors, the first request for page `c1` is a 429 with `retry_after` seconds
+    async fn mock_api(retry_after: u64) -> (Url, Arc<AtomicUsize>) {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let limited = Arc::new(AtomicUsize::new(0));
+
+        let counter = requests.clone();
+        let make_svc = make_service_fn(move |_| {
+            let requests = counter.clone();
+            let limited = limited.clone();
+
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                    requests.fetch_add(1, Ordering::SeqCst);
+
+                    let query = req.uri().query().unwrap_or_default().to_string();
+                    let res = if query.contains("cursor=c2") {
+                        HyperResponse::new(Body::from(r#"{"orders":[]}"#))
+                    } else if query.contains("cursor=c1") {
+                        if limited.fetch_add(1, Ordering::SeqCst) == 0 {
+                            HyperResponse::builder()
+                                .status(429)
+                                .header("Retry-After", retry_after.to_string())
+                                .body(Body::empty())
+                                .unwrap()
+                        } else {
+                            HyperResponse::new(Body::from(r#"{"orders":[],"cursor":"c2"}"#))
+                        }
+                    } else {
+                        HyperResponse::new(Body::from(r#"{"orders":[],"cursor":"c1"}"#))
+                    };
+
+                    async move { Ok::<_, Infallible>(res) }
+                }))
+            }
+        });
+
+        let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_svc);
+        let url = Url::parse(&format!("http://{}/v2/orders", server.local_addr())).unwrap();
+        tokio::spawn(server);
+
+        (url, requests)
+    }
+
+    #[tokio::test]
+    async fn retries_the_failed_page_only() {
+        let (url, requests) = mock_api(0).await;
+
+        let client = UniswapClient::new(1).url(url).retry(
+            RetryPolicy::new()
+                .backoff(Duration::from_millis(1), Duration::from_millis(10))
+                .rate_limit(1000.0, 10)
+                .unwrap(),
+        );
+
+        let orders = client.firehose().await.unwrap();
+
+        assert!(orders.is_empty());
+        // page 1, the 429 on page 2, page 2, page 3
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn clamps_retry_after_to_max_backoff() {
+        let (url, requests) = mock_api(3600).await;
+
+        let client = UniswapClient::new(1)
+            .url(url)
+            .retry(RetryPolicy::new().backoff(Duration::from_millis(1), Duration::from_millis(10)));
+
+        tokio::time::timeout(Duration::from_secs(5), client.firehose())
+            .await
+            .expect("retry after to be clamped")
+            .unwrap();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn without_retry_the_page_fails() {
+        let (url, _) = mock_api(0).await;
+
+        let client = UniswapClient::new(1).url(url);
+
+        assert!(matches!(
+            client.firehose().await,
+            Err(ClientError::Http { status, retry_after: Some(_) }) if status == StatusCode::TOO_MANY_REQUESTS
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_rate_limits() {
+        assert!(RetryPolicy::new().rate_limit(0.0, 1).is_err());
+        assert!(RetryPolicy::new().rate_limit(-1.0, 1).is_err());
+        assert!(RetryPolicy::new().rate_limit(f64::NAN, 1).is_err());
+        assert!(RetryPolicy::new().rate_limit(f64::INFINITY, 1).is_err());
+        assert!(RetryPolicy::new().rate_limit(1.0, 0).is_ok());
+    }
+}
@@ -0,0 +1,245 @@
+use super::response_types::OrderStatus;
+use alloy_primitives::Address;
+use serde::{Serialize, Serializer};
+use uniswapx_sdk_core::order::OrderType;
+
+/// the query params of the `GET /orders` endpoint
+///
+/// see https://github.com/Uniswap/uniswapx-service/blob/main/lib/handlers/get-orders/schema/index.ts
+///
+/// ```ignore
+/// let params = ApiParams::new(1)
+///     .order_status(OrderStatus::Open)
+///     .swapper(swapper)
+///     .sort(SortKey::CreatedAt, Sort::Gt(timestamp))
+///     .desc(true);
+/// ```
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_status: Option<OrderStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_hash: Option<String>,
+    /// sent as a comma separated list
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "comma_separated"
+    )]
+    pub order_hashes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swapper: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filler: Option<Address>,
+    /// the server requires a `sort` if theres a `sort_key`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_key: Option<SortKey>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "display_option"
+    )]
+    pub sort: Option<Sort>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desc: Option<bool>,
+    /// the cursor returned by the previous page, see [super::response_types::OrderResponse::cursor]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// the api labels exclusive dutch orders as dutch, so both are sent as `Dutch`
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "api_order_type"
+    )]
+    pub order_type: Option<OrderType>,
+}
+
+/// the fields the server can sort by
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SortKey {
+    CreatedAt,
+}
+
+/// a comparison against the [SortKey], e.g. `gt(1700000000)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+    Gt(u64),
+    Gte(u64),
+    Lt(u64),
+    Lte(u64),
+    Between(u64, u64),
+}
+
+impl ApiParams {
+    pub fn new(chain_id: usize) -> Self {
+        Self {
+            chain_id: Some(chain_id),
+            ..Default::default()
+        }
+    }
+
+    /// the max number of orders in a page
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn chain_id(mut self, chain_id: usize) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    pub fn order_status(mut self, order_status: OrderStatus) -> Self {
+        self.order_status = Some(order_status);
+        self
+    }
+
+    /// look up a single order
+    pub fn order_hash(mut self, order_hash: impl Into<String>) -> Self {
+        self.order_hash = Some(order_hash.into());
+        self
+    }
+
+    /// look up a batch of orders
+    pub fn order_hashes<I, S>(mut self, order_hashes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.order_hashes = order_hashes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn swapper(mut self, swapper: Address) -> Self {
+        self.swapper = Some(swapper);
+        self
+    }
+
+    pub fn filler(mut self, filler: Address) -> Self {
+        self.filler = Some(filler);
+        self
+    }
+
+    pub fn sort(mut self, sort_key: SortKey, sort: Sort) -> Self {
+        self.sort_key = Some(sort_key);
+        self.sort = Some(sort);
+        self
+    }
+
+    pub fn desc(mut self, desc: bool) -> Self {
+        self.desc = Some(desc);
+        self
+    }
+
+    pub fn cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+
+    /// only orders of this type, [OrderType::Dutch] and [OrderType::ExclusiveDutch] return the same orders
+    pub fn order_type(mut self, order_type: OrderType) -> Self {
+        self.order_type = Some(order_type);
+        self
+    }
+
+    /// the url encoded query string, without the leading `?`
+    pub fn as_query_string(&self) -> String {
+        serde_urlencoded::to_string(self).expect("api params to url encode")
+    }
+}
+
+impl std::fmt::Display for Sort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Sort::Gt(v) => write!(f, "gt({})", v),
+            Sort::Gte(v) => write!(f, "gte({})", v),
+            Sort::Lt(v) => write!(f, "lt({})", v),
+            Sort::Lte(v) => write!(f, "lte({})", v),
+            Sort::Between(a, b) => write!(f, "between({},{})", a, b),
+        }
+    }
+}
+
+fn comma_separated<S: Serializer>(values: &[String], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&values.join(","))
+}
+
+fn display_option<S: Serializer>(value: &Option<Sort>, serializer: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(v) => serializer.collect_str(v),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn api_order_type<S: Serializer>(
+    value: &Option<OrderType>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(OrderType::Dutch | OrderType::ExclusiveDutch) => serializer.serialize_str("Dutch"),
+        Some(OrderType::Limit) => serializer.serialize_str("Limit"),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_string() {
+        let swapper = Address::repeat_byte(1);
+        let params = ApiParams::new(1)
+            .limit(10)
+            .order_status(OrderStatus::Open)
+            .swapper(swapper)
+            .sort(SortKey::CreatedAt, Sort::Gt(1700000000))
+            .desc(true);
+
+        assert_eq!(
+            params.as_query_string(),
+            format!(
+                "limit=10&chainId=1&orderStatus=open&swapper={}&sortKey=createdAt&sort=gt%281700000000%29&desc=true",
+                swapper
+            )
+        );
+        assert_eq!(ApiParams::default().as_query_string(), "");
+    }
+
+    #[test]
+    fn order_hashes_are_comma_separated() {
+        let params = ApiParams::new(1).order_hashes(["0xa", "0xb"]);
+        assert_eq!(params.as_query_string(), "chainId=1&orderHashes=0xa%2C0xb");
+
+        let params = ApiParams::new(1).order_hashes(Vec::<String>::new());
+        assert_eq!(params.as_query_string(), "chainId=1");
+    }
+
+    #[test]
+    fn sort_is_sent_with_its_key() {
+        let params = ApiParams::default().sort(SortKey::CreatedAt, Sort::Between(1, 2));
+        assert_eq!(
+            params.as_query_string(),
+            "sortKey=createdAt&sort=between%281%2C2%29"
+        );
+    }
+
+    #[test]
+    fn order_types_use_the_api_labels() {
+        for (order_type, label) in [
+            (OrderType::Dutch, "Dutch"),
+            (OrderType::ExclusiveDutch, "Dutch"),
+            (OrderType::Limit, "Limit"),
+        ] {
+            assert_eq!(
+                ApiParams::default()
+                    .order_type(order_type)
+                    .as_query_string(),
+                format!("orderType={}", label)
+            );
+        }
+    }
+}
@@ -1,11 +1,14 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uniswapx_sdk_core::order::OrderType;
 
 /// idk where tf this comes from this is just the response from the api in the docs
 #[derive(Deserialize, Debug)]
 pub struct OrderResponseInner {
-    #[serde(rename = "type")]
-    pub order_type: OrderType,
+    /// the api labels exclusive dutch orders as dutch, and webhook notifications may not have it at all,
+    /// the order type is detected from the encoded order instead, see [crate::uniswap::decode_order]
+    #[serde(rename = "type", default)]
+    pub order_type: Option<OrderType>,
     #[serde(rename = "orderStatus")]
     pub order_status: OrderStatus,
     #[serde(rename = "chainId")]
@@ -19,12 +22,19 @@ pub struct OrderResponseInner {
     #[serde(rename = "createdAt")]
     pub created_at: u128,
 
+    /// not sent with webhook notifications
+    #[serde(default)]
     pub input: OrderInput,
+    #[serde(default)]
     pub outputs: Vec<OrderOutput>,
+
+    /// any fields not listed above, e.g. `quoteId` or `requestId`
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 //https://github.com/Uniswap/uniswapx-service/blob/main/lib/entities/Order.ts
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Default)]
 pub struct OrderInput {
     pub token: String,
     #[serde(rename = "startAmount")]
@@ -51,14 +61,27 @@ pub struct OrderResponse {
 }
 
 //https://github.com/Uniswap/uniswapx-service/blob/main/lib/entities/Order.ts
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
 pub enum OrderStatus {
-    #[serde(rename = "open")]
     Open,
     Expired,
     Error,
     Cancelled,
-    #[serde(rename = "filled")]
     Filled,
     InsufficientFunds,
 }
+
+impl OrderStatus {
+    /// the status as the api spells it
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderStatus::Open => "open",
+            OrderStatus::Expired => "expired",
+            OrderStatus::Error => "error",
+            OrderStatus::Cancelled => "cancelled",
+            OrderStatus::Filled => "filled",
+            OrderStatus::InsufficientFunds => "insufficient-funds",
+        }
+    }
+}
@@ -0,0 +1,391 @@
+use super::uniswap::{response_types::OrderResponseInner, ClientError, HashMismatchPolicy};
+use futures::stream::BoxStream;
+use hyper::{
+    body::HttpBody,
+    header::CONTENT_LENGTH,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+use tokio::sync::mpsc;
+use uniswapx_sdk_core::order::SignedOrder;
+
+#[allow(unused_imports)]
+use tracing::{debug, error, info, trace, warn};
+
+const DEFAULT_CAPACITY: usize = 1024;
+
+/// a single order is a few kilobytes
+const DEFAULT_MAX_BODY: usize = 64 * 1024;
+
+/// receives the orders the uniswapx service POSTs to registered filler webhooks
+///
+/// each request body is a single order in the shape of [OrderResponseInner],
+/// the order is decoded and passed to the stream returned with the handler
+///
+/// ```ignore
+/// let (addr, orders) = WebhookReceiver::new()
+///     .auth("x-webhook-secret", secret)
+///     .serve(([0, 0, 0, 0], 8080).into())?;
+///
+/// let orders = PushSubscriber::new(cache, orders).subscribe();
+/// ```
+pub struct WebhookReceiver {
+    auth: Option<(String, String)>,
+    capacity: usize,
+    max_body: usize,
+    hash_mismatch: HashMismatchPolicy,
+}
+
+/// a hyper handler that decodes webhook requests, see [WebhookReceiver::handler]
+#[derive(Clone)]
+pub struct WebhookHandler {
+    auth: Option<Arc<(String, String)>>,
+    max_body: usize,
+    hash_mismatch: HashMismatchPolicy,
+    orders: mpsc::Sender<SignedOrder>,
+}
+
+#[derive(Debug)]
+pub enum WebhookError {
+    Hyper(hyper::Error),
+    /// the request body is longer than the limit, see [WebhookReceiver::max_body]
+    BodyTooLarge(usize),
+    Json(serde_json::Error),
+    Client(ClientError),
+}
+
+impl Default for WebhookReceiver {
+    fn default() -> Self {
+        Self {
+            auth: None,
+            capacity: DEFAULT_CAPACITY,
+            max_body: DEFAULT_MAX_BODY,
+            hash_mismatch: HashMismatchPolicy::default(),
+        }
+    }
+}
+
+impl WebhookReceiver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// reject requests without `header` set to `value`, e.g. a secret registered with the webhook
+    pub fn auth(mut self, header: impl Into<String>, value: impl Into<String>) -> Self {
+        self.auth = Some((header.into(), value.into()));
+        self
+    }
+
+    /// how many orders can be waiting to be read from the stream,
+    /// when its full requests get a `503` so the service can retry them
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// the largest request body in bytes, longer bodies get a `413` without being read in full, defaults to 64KiB
+    pub fn max_body(mut self, max_body: usize) -> Self {
+        self.max_body = max_body;
+        self
+    }
+
+    /// what to do with orders whose `orderHash` doesnt match the encoded order, defaults to [HashMismatchPolicy::Drop]
+    pub fn hash_mismatch(mut self, policy: HashMismatchPolicy) -> Self {
+        self.hash_mismatch = policy;
+        self
+    }
+
+    /// a handler to mount in an existing server, and the stream of orders it receives
+    pub fn handler(self) -> (WebhookHandler, BoxStream<'static, SignedOrder>) {
+        let (tx, rx) = mpsc::channel(self.capacity);
+
+        let handler = WebhookHandler {
+            auth: self.auth.map(Arc::new),
+            max_body: self.max_body,
+            hash_mismatch: self.hash_mismatch,
+            orders: tx,
+        };
+
+        (handler, receiver_stream(rx))
+    }
+
+    /// bind `addr` and serve every path with [WebhookHandler::handle] in a new task
+    ///
+    /// returns the bound address, e.g. if `addr` had port 0, and the stream of orders,
+    /// the server shuts down once the stream is dropped
+    pub fn serve(
+        self,
+        addr: SocketAddr,
+    ) -> Result<(SocketAddr, BoxStream<'static, SignedOrder>), WebhookError> {
+        let (handler, orders) = self.handler();
+        let shutdown = handler.orders.clone();
+
+        let make_service = make_service_fn(move |_| {
+            let handler = handler.clone();
+
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    let handler = handler.clone();
+                    async move { Ok::<_, Infallible>(handler.handle(req).await) }
+                }))
+            }
+        });
+
+        let server = Server::try_bind(&addr)?.serve(make_service);
+        let local_addr = server.local_addr();
+
+        tokio::spawn(async move {
+            let server = server.with_graceful_shutdown(async move { shutdown.closed().await });
+
+            if let Err(e) = server.await {
+                error!("webhook receiver: server error: {}", e);
+            }
+        });
+
+        info!("webhook receiver: listening on {}", local_addr);
+
+        Ok((local_addr, orders))
+    }
+}
+
+impl WebhookHandler {
+    /// `200` once the order is queued, `400` if it cant be decoded, `401` if the auth header is wrong,
+    /// `413` if the body is too large and `503` if the stream is full or was dropped
+    pub async fn handle(&self, req: Request<Body>) -> Response<Body> {
+        if req.method() != Method::POST {
+            return status(StatusCode::METHOD_NOT_ALLOWED);
+        }
+
+        if !self.authorized(&req) {
+            warn!("webhook receiver: unauthorized request");
+            return status(StatusCode::UNAUTHORIZED);
+        }
+
+        let order = match self.decode(req).await {
+            Ok(Some(order)) => order,
+            Ok(None) => return status(StatusCode::OK),
+            Err(e @ WebhookError::BodyTooLarge(_)) => {
+                warn!("webhook receiver: {}", e);
+                return status(StatusCode::PAYLOAD_TOO_LARGE);
+            }
+            Err(e) => {
+                warn!("webhook receiver: bad request: {}", e);
+                return status(StatusCode::BAD_REQUEST);
+            }
+        };
+
+        match self.orders.try_send(order) {
+            Ok(()) => status(StatusCode::OK),
+            Err(e) => {
+                warn!("webhook receiver: cant queue order: {}", e);
+                status(StatusCode::SERVICE_UNAVAILABLE)
+            }
+        }
+    }
+
+    fn authorized(&self, req: &Request<Body>) -> bool {
+        let (header, expected) = match self.auth.as_deref() {
+            Some(auth) => auth,
+            None => return true,
+        };
+
+        match req.headers().get(header.as_str()) {
+            Some(value) => constant_time_eq(value.as_bytes(), expected.as_bytes()),
+            None => false,
+        }
+    }
+
+    /// `None` if the order was dropped by the [HashMismatchPolicy]
+    async fn decode(&self, req: Request<Body>) -> Result<Option<SignedOrder>, WebhookError> {
+        let body = self.read_body(req).await?;
+        let inner: OrderResponseInner = serde_json::from_slice(&body)?;
+
+        Ok(self.hash_mismatch.decode(inner)?)
+    }
+
+    /// read the body, failing as soon as the `Content-Length` or the bytes read pass [WebhookReceiver::max_body]
+    async fn read_body(&self, req: Request<Body>) -> Result<Vec<u8>, WebhookError> {
+        let content_length = req
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|len| len.to_str().ok())
+            .and_then(|len| len.parse::<u64>().ok());
+
+        if content_length.is_some_and(|len| len > self.max_body as u64) {
+            return Err(WebhookError::BodyTooLarge(self.max_body));
+        }
+
+        let mut body = req.into_body();
+        let mut buf = Vec::new();
+
+        while let Some(chunk) = body.data().await {
+            let chunk = chunk?;
+
+            if buf.len() + chunk.len() > self.max_body {
+                return Err(WebhookError::BodyTooLarge(self.max_body));
+            }
+
+            buf.extend_from_slice(&chunk);
+        }
+
+        Ok(buf)
+    }
+}
+
+fn receiver_stream(mut rx: mpsc::Receiver<SignedOrder>) -> BoxStream<'static, SignedOrder> {
+    Box::pin(async_stream::stream! {
+        while let Some(order) = rx.recv().await {
+            yield order;
+        }
+    })
+}
+
+fn status(status: StatusCode) -> Response<Body> {
+    let mut res = Response::new(Body::empty());
+    *res.status_mut() = status;
+    res
+}
+
+/// compare secrets without leaking where they differ through timing
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+impl std::error::Error for WebhookError {}
+
+impl std::fmt::Display for WebhookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WebhookError::Hyper(e) => write!(f, "Hyper error: {}", e),
+            WebhookError::BodyTooLarge(max) => write!(f, "Body too large, max {} bytes", max),
+            WebhookError::Json(e) => write!(f, "Json error: {}", e),
+            WebhookError::Client(e) => write!(f, "Client error: {}", e),
+        }
+    }
+}
+
+impl From<hyper::Error> for WebhookError {
+    fn from(e: hyper::Error) -> Self {
+        Self::Hyper(e)
+    }
+}
+
+impl From<serde_json::Error> for WebhookError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<ClientError> for WebhookError {
+    fn from(e: ClientError) -> Self {
+        Self::Client(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::api_order;
+    use futures::StreamExt;
+    use uniswapx_sdk_core::{order::OrderType, test_utils::dutch};
+
+    /// a webhook notification, which has no `type`
+    fn notification() -> String {
+        let mut inner = api_order(&dutch().signed("0x".to_string()));
+        inner.as_object_mut().unwrap().remove("type");
+
+        inner.to_string()
+    }
+
+    fn post(body: Body) -> Request<Body> {
+        Request::post("/").body(body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn decodes_orders_without_a_type() {
+        let (handler, mut orders) = WebhookReceiver::new().handler();
+
+        let res = handler.handle(post(Body::from(notification()))).await;
+
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(orders.next().await.unwrap().order_type(), OrderType::Dutch);
+    }
+
+    #[tokio::test]
+    async fn rejects_large_bodies() {
+        let (handler, _orders) = WebhookReceiver::new().max_body(16).handler();
+
+        let res = handler.handle(post(Body::from(notification()))).await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        // no content length, the limit is checked as the chunks are read
+        let (mut tx, body) = Body::channel();
+        tokio::spawn(async move {
+            for _ in 0..4 {
+                if tx.send_data(vec![b' '; 8].into()).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let res = handler.handle(post(body)).await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_or_missing_secrets() {
+        let (handler, mut orders) = WebhookReceiver::new()
+            .auth("x-webhook-secret", "secret")
+            .handler();
+
+        let with_secret = |secret: Option<&str>| {
+            let mut req = post(Body::from(notification()));
+            if let Some(secret) = secret {
+                req.headers_mut()
+                    .insert("x-webhook-secret", secret.parse().unwrap());
+            }
+            req
+        };
+
+        for secret in [None, Some(""), Some("secreT"), Some("secret2")] {
+            let res = handler.handle(with_secret(secret)).await;
+            assert_eq!(res.status(), StatusCode::UNAUTHORIZED, "{:?}", secret);
+        }
+
+        let res = handler.handle(with_secret(Some("secret"))).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            orders.next().await.unwrap().order_hash(),
+            dutch().order_hash()
+        );
+    }
+
+    #[tokio::test]
+    async fn serves_notifications() {
+        let (addr, mut orders) = WebhookReceiver::new()
+            .capacity(1)
+            .serve(([127, 0, 0, 1], 0).into())
+            .unwrap();
+        let url = format!("http://{}/webhook", addr);
+        let client = reqwest::Client::new();
+
+        let res = client.post(&url).body(notification()).send().await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // the stream is full until the first order is read
+        let res = client.post(&url).body(notification()).send().await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let res = client.post(&url).body("{}").send().await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let res = client.get(&url).send().await.unwrap();
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        assert_eq!(
+            orders.next().await.unwrap().order_hash(),
+            dutch().order_hash()
+        );
+    }
+}
@@ -0,0 +1,399 @@
+use crate::{order::OrderType, revert::into_ethers_address, signing::PERMIT2_ADDRESS};
+use alloy_primitives::Address;
+use ethers::providers::Middleware;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::Path};
+
+#[allow(unused_imports)]
+use tracing::{debug, error, info, trace, warn};
+
+// https://github.com/Uniswap/uniswapx-sdk/blob/main/src/constants.ts
+//
+// the v1 reactors are deployed with create2, the sdk maps them to the same address on mainnet, goerli and polygon
+//
+// theres no shared LimitOrderReactor deployment, so limit orders are unsupported until one is set with an override
+const DUTCH_REACTOR: &str = "0xE80bF394d190851E215D5F67B67f8F5A52783F1E";
+const EXCLUSIVE_DUTCH_REACTOR: &str = "0x6000da47483062A0D734Ba3dc7576Ce6A0B645C4";
+// see: https://docs.uniswap.org/contracts/uniswapx/overview
+const ORDER_QUOTER: &str = "0x7714520f383C998e8822E8743FD6f90A2979689b";
+// see: https://www.multicall3.com
+const MULTICALL3: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+// see: https://docs.uniswap.org/contracts/v3/reference/deployments
+const SWAP_ROUTER_02: &str = "0x68b3465833fb72A70ecDF485E0e4C7bD8665Fc45";
+
+pub const MAINNET: u64 = 1;
+pub const GOERLI: u64 = 5;
+pub const POLYGON: u64 = 137;
+pub const ARBITRUM: u64 = 42161;
+
+/// the addresses of the contracts needed to validate and fill orders on a single chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainConfig {
+    pub chain_id: u64,
+    /// the reactor deployments by order type, an order type with no reactor is unsupported on this chain
+    #[serde(default)]
+    pub reactors: HashMap<OrderType, Address>,
+    pub order_quoter: Address,
+    #[serde(default = "default_permit2")]
+    pub permit2: Address,
+    /// used to batch the state checks, see [crate::order::SignedOrder::validate_state_ethers]
+    #[serde(default = "default_multicall")]
+    pub multicall: Address,
+    pub weth: Address,
+    /// the router a [ChainConfig::swap_router_02_executor] swaps through
+    #[serde(default)]
+    pub swap_router_02: Option<Address>,
+    /// a `SwapRouter02Executor` to fill through
+    ///
+    /// the executor only accepts calls from its whitelisted caller, so theres no shared deployment,
+    /// set this to your own deployment with [AddressBook::load_overrides]
+    #[serde(default)]
+    pub swap_router_02_executor: Option<Address>,
+}
+
+/// a mapping of chain id to [ChainConfig]
+///
+/// [AddressBook::default] contains the built in deployments, which can be overridden from a file with [AddressBook::load_overrides]
+#[derive(Debug, Clone)]
+pub struct AddressBook {
+    chains: HashMap<u64, ChainConfig>,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Toml(toml::de::Error),
+    UnknownFormat(String),
+    /// an override for a chain without a config is missing a field that has no default
+    MissingField {
+        chain_id: u64,
+        field: &'static str,
+    },
+}
+
+/// the file format of an overrides file
+///
+/// a list of [ChainOverride]s, for toml use a `[[chains]]` table array
+#[derive(Deserialize)]
+struct Overrides {
+    chains: Vec<ChainOverride>,
+}
+
+/// the fields of a [ChainConfig] to replace, every field but the chain id is optional
+///
+/// reactors are merged by order type, so overriding one reactor keeps the others
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChainOverride {
+    chain_id: u64,
+    #[serde(default)]
+    reactors: HashMap<OrderType, Address>,
+    order_quoter: Option<Address>,
+    permit2: Option<Address>,
+    multicall: Option<Address>,
+    weth: Option<Address>,
+    swap_router_02: Option<Address>,
+    swap_router_02_executor: Option<Address>,
+}
+
+impl ChainConfig {
+    /// the built in config for a chain, if there is one
+    ///
+    /// these are the known deployments, see [ChainConfig::missing_deployments_ethers] to check them against a node
+    pub fn builtin(chain_id: u64) -> Option<Self> {
+        let config = match chain_id {
+            MAINNET => Self::v1(
+                chain_id,
+                "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+                v1_reactors(),
+            ),
+            GOERLI => Self::v1(
+                chain_id,
+                "0xB4FBF271143F4FBf7B91A5ded31805e42b2208d6",
+                v1_reactors(),
+            ),
+            POLYGON => Self::v1(
+                chain_id,
+                // wrapped matic
+                "0x0d500B1d8E8eF31E21C99d1Db9A6444d3ADf1270",
+                v1_reactors(),
+            ),
+            // uniswapx launched on arbitrum with the v2 reactor, which this crate doesnt support yet,
+            // so no order type has a reactor here until one is added with an override
+            ARBITRUM => Self::v1(
+                chain_id,
+                "0x82aF49447D8a07e3bd95BD0d56f35241523fBab1",
+                HashMap::new(),
+            ),
+            _ => return None,
+        };
+
+        Some(config)
+    }
+
+    fn v1(chain_id: u64, weth: &str, reactors: HashMap<OrderType, Address>) -> Self {
+        Self {
+            chain_id,
+            reactors,
+            order_quoter: parse_address(ORDER_QUOTER),
+            permit2: default_permit2(),
+            multicall: default_multicall(),
+            weth: parse_address(weth),
+            swap_router_02: Some(parse_address(SWAP_ROUTER_02)),
+            swap_router_02_executor: None,
+        }
+    }
+
+    /// every configured contract address with no code on the chain `middleware` is connected to
+    ///
+    /// an empty result means every contract is deployed, not that its the right contract
+    pub async fn missing_deployments_ethers<M: Middleware>(
+        &self,
+        middleware: &M,
+    ) -> Result<Vec<Address>, M::Error> {
+        let mut addresses: Vec<Address> = self.reactors.values().copied().collect();
+        addresses.extend([self.order_quoter, self.permit2, self.multicall, self.weth]);
+        addresses.extend(self.swap_router_02);
+        addresses.extend(self.swap_router_02_executor);
+        addresses.sort();
+        addresses.dedup();
+
+        let mut missing = Vec::new();
+        for address in addresses {
+            let code = middleware
+                .get_code(into_ethers_address(address), None)
+                .await?;
+
+            if code.is_empty() {
+                warn!("chain {}: no code at {}", self.chain_id, address);
+                missing.push(address);
+            }
+        }
+
+        Ok(missing)
+    }
+
+    pub fn reactor(&self, order_type: OrderType) -> Option<Address> {
+        self.reactors.get(&order_type).copied()
+    }
+
+    /// the order types a reactor deployment fills, empty if its not a known reactor
+    ///
+    /// a reactor can be configured for more than one order type
+    pub fn order_types_of(&self, reactor: Address) -> Vec<OrderType> {
+        self.reactors
+            .iter()
+            .filter(|(_, address)| **address == reactor)
+            .map(|(order_type, _)| *order_type)
+            .collect()
+    }
+}
+
+impl AddressBook {
+    /// an empty address book
+    pub fn empty() -> Self {
+        Self {
+            chains: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, chain_id: u64) -> Option<&ChainConfig> {
+        self.chains.get(&chain_id)
+    }
+
+    /// insert or replace the config for a chain
+    pub fn insert(&mut self, config: ChainConfig) {
+        self.chains.insert(config.chain_id, config);
+    }
+
+    pub fn chains(&self) -> impl Iterator<Item = &ChainConfig> {
+        self.chains.values()
+    }
+
+    /// merge the overrides in a `.json` or `.toml` file into the configs, field by field
+    ///
+    /// an override for a chain without a config must have at least an `orderQuoter` and `weth`
+    pub fn load_overrides(&mut self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+
+        let overrides = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::parse_json(&contents)?,
+            Some("toml") => Self::parse_toml(&contents)?,
+            other => return Err(ConfigError::UnknownFormat(format!("{:?}", other))),
+        };
+
+        for chain in overrides {
+            let config = chain.merge(self.chains.remove(&chain.chain_id))?;
+            self.insert(config);
+        }
+
+        Ok(())
+    }
+
+    fn parse_json(contents: &str) -> Result<Vec<ChainOverride>, ConfigError> {
+        Ok(serde_json::from_str::<Overrides>(contents)?.chains)
+    }
+
+    fn parse_toml(contents: &str) -> Result<Vec<ChainOverride>, ConfigError> {
+        Ok(toml::from_str::<Overrides>(contents)?.chains)
+    }
+}
+
+impl ChainOverride {
+    /// apply the override on top of `base`, or build a new config if theres none
+    fn merge(&self, base: Option<ChainConfig>) -> Result<ChainConfig, ConfigError> {
+        let missing = |field| ConfigError::MissingField {
+            chain_id: self.chain_id,
+            field,
+        };
+
+        let mut config = match base {
+            Some(base) => base,
+            None => ChainConfig {
+                chain_id: self.chain_id,
+                reactors: HashMap::new(),
+                order_quoter: self.order_quoter.ok_or_else(|| missing("orderQuoter"))?,
+                permit2: default_permit2(),
+                multicall: default_multicall(),
+                weth: self.weth.ok_or_else(|| missing("weth"))?,
+                swap_router_02: None,
+                swap_router_02_executor: None,
+            },
+        };
+
+        config.reactors.extend(&self.reactors);
+        config.order_quoter = self.order_quoter.unwrap_or(config.order_quoter);
+        config.permit2 = self.permit2.unwrap_or(config.permit2);
+        config.multicall = self.multicall.unwrap_or(config.multicall);
+        config.weth = self.weth.unwrap_or(config.weth);
+        config.swap_router_02 = self.swap_router_02.or(config.swap_router_02);
+        config.swap_router_02_executor = self
+            .swap_router_02_executor
+            .or(config.swap_router_02_executor);
+
+        Ok(config)
+    }
+}
+
+impl Default for AddressBook {
+    /// the built in deployments for mainnet, goerli, polygon and arbitrum
+    fn default() -> Self {
+        let mut book = Self::empty();
+
+        for chain_id in [MAINNET, GOERLI, POLYGON, ARBITRUM] {
+            book.insert(ChainConfig::builtin(chain_id).expect("builtin chain config"));
+        }
+
+        book
+    }
+}
+
+fn v1_reactors() -> HashMap<OrderType, Address> {
+    HashMap::from([
+        (OrderType::Dutch, parse_address(DUTCH_REACTOR)),
+        (
+            OrderType::ExclusiveDutch,
+            parse_address(EXCLUSIVE_DUTCH_REACTOR),
+        ),
+    ])
+}
+
+fn default_permit2() -> Address {
+    parse_address(PERMIT2_ADDRESS)
+}
+
+fn default_multicall() -> Address {
+    parse_address(MULTICALL3)
+}
+
+fn parse_address(address: &str) -> Address {
+    address.parse().expect("builtin address to parse")
+}
+
+impl std::error::Error for ConfigError {}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "Io error: {}", e),
+            ConfigError::Json(e) => write!(f, "Json error: {}", e),
+            ConfigError::Toml(e) => write!(f, "Toml error: {}", e),
+            ConfigError::UnknownFormat(ext) => write!(f, "Unknown config format: {}", ext),
+            ConfigError::MissingField { chain_id, field } => {
+                write!(f, "Missing field {} for chain {}", field, chain_id)
+            }
+        }
+    }
+}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(e: std::io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ConfigError {
+    fn from(e: serde_json::Error) -> Self {
+        ConfigError::Json(e)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(e: toml::de::Error) -> Self {
+        ConfigError::Toml(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_overrides_merge_into_the_builtin_config() {
+        let executor = Address::repeat_byte(1);
+        let limit = Address::repeat_byte(2);
+        let json = format!(
+            r#"{{ "chains": [{{ "chainId": 1, "swapRouter02Executor": "{}", "reactors": {{ "Limit": "{}" }} }}] }}"#,
+            executor, limit
+        );
+
+        let mut book = AddressBook::default();
+        for chain in AddressBook::parse_json(&json).unwrap() {
+            let config = chain.merge(book.chains.remove(&chain.chain_id)).unwrap();
+            book.insert(config);
+        }
+
+        let builtin = ChainConfig::builtin(MAINNET).unwrap();
+        let config = book.get(MAINNET).unwrap();
+
+        assert_eq!(config.swap_router_02_executor, Some(executor));
+        assert_eq!(config.reactor(OrderType::Limit), Some(limit));
+        assert_eq!(
+            config.reactor(OrderType::Dutch),
+            builtin.reactor(OrderType::Dutch)
+        );
+        assert_eq!(config.order_quoter, builtin.order_quoter);
+        assert_eq!(config.weth, builtin.weth);
+    }
+
+    #[test]
+    fn new_chains_need_a_quoter_and_weth() {
+        let toml = r#"
+            [[chains]]
+            chainId = 10
+            weth = "0x4200000000000000000000000000000000000006"
+        "#;
+
+        let chain = AddressBook::parse_toml(toml).unwrap().remove(0);
+        assert!(matches!(
+            chain.merge(None),
+            Err(ConfigError::MissingField {
+                chain_id: 10,
+                field: "orderQuoter"
+            })
+        ));
+    }
+}
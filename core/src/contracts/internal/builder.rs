@@ -0,0 +1,416 @@
+use super::{
+    super::common::{InputToken, OrderInfo, OutputToken},
+    dutch::{DutchInput, DutchOrder, DutchOutput},
+    exclusive_dutch::ExclusiveDutchOrder,
+    limit::LimitOrder,
+};
+use crate::{
+    order::Order,
+    resolver::{ResolutionError, Resolve},
+};
+use alloy_primitives::{Address, U256};
+
+/// the errors that can occur when building an order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuilderError {
+    /// a required field was never set
+    MissingField(&'static str),
+    /// the order would be rejected by the reactor
+    InvalidOrder(ResolutionError),
+}
+
+/// builds the [OrderInfo] shared by every order type
+#[derive(Clone, Default)]
+struct OrderInfoBuilder {
+    reactor: Option<Address>,
+    swapper: Option<Address>,
+    nonce: Option<U256>,
+    deadline: Option<U256>,
+    additional_validation_contract: Address,
+    additional_validation_data: Vec<u8>,
+}
+
+/// a builder for a [DutchOrder]
+///
+/// ```ignore
+/// let order = DutchOrderBuilder::new()
+///     .reactor(reactor)
+///     .swapper(swapper)
+///     .nonce(nonce)
+///     .deadline(deadline)
+///     .decay_start_time(start)
+///     .decay_end_time(end)
+///     .input(weth, amount, amount)
+///     .output(usdc, start_amount, end_amount, swapper)
+///     .build()?;
+/// ```
+#[derive(Clone, Default)]
+pub struct DutchOrderBuilder {
+    info: OrderInfoBuilder,
+    decay_start_time: Option<U256>,
+    decay_end_time: Option<U256>,
+    input: Option<DutchInput>,
+    outputs: Vec<DutchOutput>,
+}
+
+/// a builder for an [ExclusiveDutchOrder]
+///
+/// if no exclusive filler is set the order is open to everyone
+#[derive(Clone, Default)]
+pub struct ExclusiveDutchOrderBuilder {
+    dutch: DutchOrderBuilder,
+    exclusive_filler: Address,
+    exclusivity_override_bps: U256,
+}
+
+/// a builder for a [LimitOrder]
+#[derive(Clone, Default)]
+pub struct LimitOrderBuilder {
+    info: OrderInfoBuilder,
+    input: Option<InputToken>,
+    outputs: Vec<OutputToken>,
+}
+
+/// implements the [OrderInfo] setters for a builder
+macro_rules! impl_info_setters {
+    ($builder:ty, $($path:ident).+) => {
+        impl $builder {
+            /// the reactor this order is targeting
+            pub fn reactor(mut self, reactor: Address) -> Self {
+                self.$($path).+.reactor = Some(reactor);
+                self
+            }
+
+            /// the swapper creating the order
+            pub fn swapper(mut self, swapper: Address) -> Self {
+                self.$($path).+.swapper = Some(swapper);
+                self
+            }
+
+            /// the permit2 nonce of the order
+            pub fn nonce(mut self, nonce: U256) -> Self {
+                self.$($path).+.nonce = Some(nonce);
+                self
+            }
+
+            /// the timestamp after which the order is no longer valid
+            pub fn deadline(mut self, deadline: U256) -> Self {
+                self.$($path).+.deadline = Some(deadline);
+                self
+            }
+
+            /// a custom validation contract and its encoded params, defaults to none
+            pub fn additional_validation(mut self, contract: Address, data: Vec<u8>) -> Self {
+                self.$($path).+.additional_validation_contract = contract;
+                self.$($path).+.additional_validation_data = data;
+                self
+            }
+        }
+    };
+}
+
+impl_info_setters!(DutchOrderBuilder, info);
+impl_info_setters!(ExclusiveDutchOrderBuilder, dutch.info);
+impl_info_setters!(LimitOrderBuilder, info);
+
+impl OrderInfoBuilder {
+    fn build(self) -> Result<OrderInfo, BuilderError> {
+        Ok(OrderInfo {
+            reactor: self.reactor.ok_or(BuilderError::MissingField("reactor"))?,
+            swapper: self.swapper.ok_or(BuilderError::MissingField("swapper"))?,
+            nonce: self.nonce.ok_or(BuilderError::MissingField("nonce"))?,
+            deadline: self
+                .deadline
+                .ok_or(BuilderError::MissingField("deadline"))?,
+            additionalValidationContract: self.additional_validation_contract,
+            additionalValidationData: self.additional_validation_data,
+        })
+    }
+}
+
+impl DutchOrderBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the time at which the amounts start decaying
+    pub fn decay_start_time(mut self, decay_start_time: U256) -> Self {
+        self.decay_start_time = Some(decay_start_time);
+        self
+    }
+
+    /// the time at which the amounts stop decaying
+    pub fn decay_end_time(mut self, decay_end_time: U256) -> Self {
+        self.decay_end_time = Some(decay_end_time);
+        self
+    }
+
+    /// the token the swapper provides, the amount increases from `start_amount` to `end_amount`
+    pub fn input(mut self, token: Address, start_amount: U256, end_amount: U256) -> Self {
+        self.input = Some(DutchInput {
+            token,
+            startAmount: start_amount,
+            endAmount: end_amount,
+        });
+        self
+    }
+
+    /// adds an output, the amount decreases from `start_amount` to `end_amount`
+    pub fn output(
+        mut self,
+        token: Address,
+        start_amount: U256,
+        end_amount: U256,
+        recipient: Address,
+    ) -> Self {
+        self.outputs.push(DutchOutput {
+            token,
+            startAmount: start_amount,
+            endAmount: end_amount,
+            recipient,
+        });
+        self
+    }
+
+    /// build the inner [DutchOrder] without checking it against the reactor rules
+    fn build_unchecked(self) -> Result<DutchOrder, BuilderError> {
+        if self.outputs.is_empty() {
+            return Err(BuilderError::MissingField("outputs"));
+        }
+
+        Ok(DutchOrder {
+            info: self.info.build()?,
+            decayStartTime: self
+                .decay_start_time
+                .ok_or(BuilderError::MissingField("decay_start_time"))?,
+            decayEndTime: self
+                .decay_end_time
+                .ok_or(BuilderError::MissingField("decay_end_time"))?,
+            input: self.input.ok_or(BuilderError::MissingField("input"))?,
+            outputs: self.outputs,
+        })
+    }
+
+    /// build the order, rejecting anything the reactor would revert on
+    pub fn build(self) -> Result<Order, BuilderError> {
+        let order = self.build_unchecked()?;
+
+        // resolving checks everything the reactor does
+        order.resolve_at(U256::ZERO)?;
+
+        Ok(Order::from(order))
+    }
+}
+
+impl ExclusiveDutchOrderBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the time at which the amounts start decaying, this is also the end of the exclusivity period
+    pub fn decay_start_time(mut self, decay_start_time: U256) -> Self {
+        self.dutch = self.dutch.decay_start_time(decay_start_time);
+        self
+    }
+
+    /// the time at which the amounts stop decaying
+    pub fn decay_end_time(mut self, decay_end_time: U256) -> Self {
+        self.dutch = self.dutch.decay_end_time(decay_end_time);
+        self
+    }
+
+    /// the filler with exclusive rights until the decay start time
+    ///
+    /// an `override_bps` of zero makes the order strictly exclusive
+    pub fn exclusivity(mut self, exclusive_filler: Address, override_bps: U256) -> Self {
+        self.exclusive_filler = exclusive_filler;
+        self.exclusivity_override_bps = override_bps;
+        self
+    }
+
+    /// the token the swapper provides, the amount increases from `start_amount` to `end_amount`
+    pub fn input(mut self, token: Address, start_amount: U256, end_amount: U256) -> Self {
+        self.dutch = self.dutch.input(token, start_amount, end_amount);
+        self
+    }
+
+    /// adds an output, the amount decreases from `start_amount` to `end_amount`
+    pub fn output(
+        mut self,
+        token: Address,
+        start_amount: U256,
+        end_amount: U256,
+        recipient: Address,
+    ) -> Self {
+        self.dutch = self
+            .dutch
+            .output(token, start_amount, end_amount, recipient);
+        self
+    }
+
+    /// build the order, rejecting anything the reactor would revert on
+    pub fn build(self) -> Result<Order, BuilderError> {
+        let dutch = self.dutch.build_unchecked()?;
+
+        let order = ExclusiveDutchOrder {
+            info: dutch.info,
+            decayStartTime: dutch.decayStartTime,
+            decayEndTime: dutch.decayEndTime,
+            exclusiveFiller: self.exclusive_filler,
+            exclusivityOverrideBps: self.exclusivity_override_bps,
+            input: dutch.input,
+            outputs: dutch.outputs,
+        };
+
+        // resolving checks everything the reactor does
+        order.resolve_at(U256::ZERO)?;
+
+        Ok(Order::from(order))
+    }
+}
+
+impl LimitOrderBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the token and exact amount the swapper provides
+    pub fn input(mut self, token: Address, amount: U256) -> Self {
+        self.input = Some(InputToken {
+            token,
+            amount,
+            maxAmount: amount,
+        });
+        self
+    }
+
+    /// adds an output with an exact amount
+    pub fn output(mut self, token: Address, amount: U256, recipient: Address) -> Self {
+        self.outputs.push(OutputToken {
+            token,
+            amount,
+            recipient,
+        });
+        self
+    }
+
+    pub fn build(self) -> Result<Order, BuilderError> {
+        if self.outputs.is_empty() {
+            return Err(BuilderError::MissingField("outputs"));
+        }
+
+        Ok(Order::from(LimitOrder {
+            info: self.info.build()?,
+            input: self.input.ok_or(BuilderError::MissingField("input"))?,
+            outputs: self.outputs,
+        }))
+    }
+}
+
+impl std::error::Error for BuilderError {}
+
+impl std::fmt::Display for BuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuilderError::MissingField(field) => write!(f, "MissingField: {}", field),
+            BuilderError::InvalidOrder(e) => write!(f, "InvalidOrder: {}", e),
+        }
+    }
+}
+
+impl From<ResolutionError> for BuilderError {
+    fn from(e: ResolutionError) -> Self {
+        BuilderError::InvalidOrder(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{order::OrderType, test_utils::*};
+
+    #[test]
+    fn builds_each_order_type() {
+        let order = dutch();
+        assert_eq!(order.order_type(), OrderType::Dutch);
+        assert_eq!(order.swapper(), swapper());
+        assert_eq!(order.deadline(), u(2000));
+
+        let order = exclusive_dutch();
+        assert_eq!(order.order_type(), OrderType::ExclusiveDutch);
+        assert_eq!(order.exclusive_filler(), Some(EXCLUSIVE_FILLER));
+
+        let order = limit();
+        assert_eq!(order.order_type(), OrderType::Limit);
+        assert_eq!(order.input().startAmount, u(100));
+    }
+
+    #[test]
+    fn missing_fields() {
+        assert!(matches!(
+            DutchOrderBuilder::new().build(),
+            Err(BuilderError::MissingField("outputs"))
+        ));
+        assert!(matches!(
+            DutchOrderBuilder::new()
+                .output(OUTPUT_TOKEN, u(1), u(1), swapper())
+                .build(),
+            Err(BuilderError::MissingField("reactor"))
+        ));
+        assert!(matches!(
+            ExclusiveDutchOrderBuilder::new()
+                .reactor(Address::repeat_byte(5))
+                .swapper(swapper())
+                .nonce(u(1))
+                .deadline(u(2000))
+                .output(OUTPUT_TOKEN, u(1), u(1), swapper())
+                .build(),
+            Err(BuilderError::MissingField("decay_start_time"))
+        ));
+        assert!(matches!(
+            LimitOrderBuilder::new()
+                .reactor(Address::repeat_byte(5))
+                .swapper(swapper())
+                .nonce(u(1))
+                .deadline(u(2000))
+                .output(OUTPUT_TOKEN, u(1), swapper())
+                .build(),
+            Err(BuilderError::MissingField("input"))
+        ));
+    }
+
+    #[test]
+    fn invalid_decay() {
+        assert_eq!(
+            dutch_builder().decay_end_time(u(999)).build().err(),
+            Some(BuilderError::InvalidOrder(
+                ResolutionError::EndTimeBeforeStartTime
+            ))
+        );
+        assert_eq!(
+            dutch_builder().deadline(u(1999)).build().err(),
+            Some(BuilderError::InvalidOrder(
+                ResolutionError::DeadlineBeforeEndTime
+            ))
+        );
+        // the output decays upwards
+        assert_eq!(
+            exclusive_dutch_builder()
+                .output(OUTPUT_TOKEN, u(200), u(300), swapper())
+                .build()
+                .err(),
+            Some(BuilderError::InvalidOrder(
+                ResolutionError::IncorrectAmounts
+            ))
+        );
+        assert_eq!(
+            dutch_builder()
+                .input(INPUT_TOKEN, u(100), u(200))
+                .build()
+                .err(),
+            Some(BuilderError::InvalidOrder(
+                ResolutionError::InputAndOutputDecay
+            ))
+        );
+    }
+}
@@ -2,4 +2,5 @@ pub mod dutch;
 pub mod exclusive_dutch;
 pub mod limit;
 
-// todo! builders
+/// validating builders for the order types, see [builder::DutchOrderBuilder]
+pub mod builder;
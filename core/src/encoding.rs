@@ -0,0 +1,709 @@
+use crate::{
+    config::ChainConfig,
+    contracts::{
+        common::{InputToken, OrderInfo, OutputToken},
+        internal::{
+            dutch::{DutchInput, DutchOrder, DutchOutput},
+            exclusive_dutch::ExclusiveDutchOrder,
+            limit::LimitOrder,
+        },
+    },
+    order::{Order, OrderMetadata, OrderType, SignedOrder},
+};
+use alloy_primitives::{Address, B256, U256};
+use serde::{Deserialize, Serialize};
+
+/// the shape of a signed order in the uniswap api
+///
+/// this is the default serde shape of [SignedOrder], `chainId` is taken from the [OrderMetadata] unless set with [SignedOrder::to_api]
+///
+/// note: any other [OrderMetadata] is lost, use [decoded] to keep it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiSignedOrder {
+    /// the abi encoded order, hex encoded with a `0x` prefix
+    pub encoded_order: String,
+    pub signature: String,
+    #[serde(rename = "type")]
+    pub order_type: OrderType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<u64>,
+    /// the order hash as computed by the reactor, see [Order::order_hash]
+    pub order_hash: String,
+}
+
+/// a fully decoded signed order, amounts are decimal strings
+///
+/// see [decoded] to use this shape with `#[serde(with)]`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedSignedOrder {
+    pub order: DecodedOrder,
+    pub signature: String,
+    pub order_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<OrderMetadata>,
+}
+
+/// a fully decoded order, tagged by its [OrderType]
+///
+/// this is the default serde shape of [Order]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum DecodedOrder {
+    Dutch(DecodedDutchOrder),
+    Limit(DecodedLimitOrder),
+    ExclusiveDutch(DecodedExclusiveDutchOrder),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedOrderInfo {
+    pub reactor: Address,
+    pub swapper: Address,
+    #[serde(with = "decimal")]
+    pub nonce: U256,
+    #[serde(with = "decimal")]
+    pub deadline: U256,
+    pub additional_validation_contract: Address,
+    /// hex encoded with a `0x` prefix
+    pub additional_validation_data: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedDutchInput {
+    pub token: Address,
+    #[serde(with = "decimal")]
+    pub start_amount: U256,
+    #[serde(with = "decimal")]
+    pub end_amount: U256,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedDutchOutput {
+    pub token: Address,
+    #[serde(with = "decimal")]
+    pub start_amount: U256,
+    #[serde(with = "decimal")]
+    pub end_amount: U256,
+    pub recipient: Address,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedDutchOrder {
+    pub info: DecodedOrderInfo,
+    #[serde(with = "decimal")]
+    pub decay_start_time: U256,
+    #[serde(with = "decimal")]
+    pub decay_end_time: U256,
+    pub input: DecodedDutchInput,
+    pub outputs: Vec<DecodedDutchOutput>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedExclusiveDutchOrder {
+    pub info: DecodedOrderInfo,
+    #[serde(with = "decimal")]
+    pub decay_start_time: U256,
+    #[serde(with = "decimal")]
+    pub decay_end_time: U256,
+    pub exclusive_filler: Address,
+    #[serde(with = "decimal")]
+    pub exclusivity_override_bps: U256,
+    pub input: DecodedDutchInput,
+    pub outputs: Vec<DecodedDutchOutput>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedInputToken {
+    pub token: Address,
+    #[serde(with = "decimal")]
+    pub amount: U256,
+    #[serde(with = "decimal")]
+    pub max_amount: U256,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedOutputToken {
+    pub token: Address,
+    #[serde(with = "decimal")]
+    pub amount: U256,
+    pub recipient: Address,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedLimitOrder {
+    pub info: DecodedOrderInfo,
+    pub input: DecodedInputToken,
+    pub outputs: Vec<DecodedOutputToken>,
+}
+
+#[derive(Debug)]
+pub enum EncodingError {
+    /// the encoded order doesnt match the abi of its order type
+    Abi(alloy_sol_types::Error),
+    /// some bytes field isnt valid hex
+    Hex(hex::FromHexError),
+    /// the encoded order doesnt decode as any order type
+    UnknownLayout,
+    /// the encoded order decodes as more than one order type, and neither the reactor or order hash tell them apart
+    AmbiguousOrderType(Vec<OrderType>),
+    /// the `orderHash` isnt a 32 byte hex string
+    InvalidOrderHash(String),
+    /// the `orderHash` isnt the hash of the encoded order
+    HashMismatch { expected: B256, computed: B256 },
+}
+
+/// serialize a [SignedOrder] as a [DecodedSignedOrder]
+///
+/// ```ignore
+/// #[derive(Serialize, Deserialize)]
+/// struct Persisted {
+///     #[serde(with = "uniswapx_sdk_core::encoding::decoded")]
+///     order: SignedOrder,
+/// }
+/// ```
+pub mod decoded {
+    use super::DecodedSignedOrder;
+    use crate::order::SignedOrder;
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(order: &SignedOrder, serializer: S) -> Result<S::Ok, S::Error> {
+        DecodedSignedOrder::from(order).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<SignedOrder, D::Error> {
+        SignedOrder::try_from(DecodedSignedOrder::deserialize(deserializer)?)
+            .map_err(D::Error::custom)
+    }
+}
+
+/// (de)serialize a [U256] as a decimal string, `0x` prefixed hex is also accepted when deserializing
+mod decimal {
+    use alloy_primitives::U256;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &U256, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<U256, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// decode an abi encoded order without trusting a declared order type
+///
+/// the uniswap api labels exclusive dutch orders as dutch, so the type is detected by:
+/// 1. decoding with every order layout
+/// 2. picking the layout that re-hashes to `order_hash`
+/// 3. otherwise picking the layout whose reactor is the reactor for that type in `config`
+/// 4. otherwise the only layout that decodes
+///
+/// see [ChainConfig::builtin] for the built in reactors of a chain
+pub fn detect_order(
+    encoded_order: &str,
+    config: Option<&ChainConfig>,
+    order_hash: Option<B256>,
+) -> Result<Order, EncodingError> {
+    let candidates: Vec<Order> = [
+        ExclusiveDutchOrder::try_from(encoded_order.to_string())
+            .map(Order::from)
+            .ok(),
+        DutchOrder::try_from(encoded_order.to_string())
+            .map(Order::from)
+            .ok(),
+        LimitOrder::try_from(encoded_order.to_string())
+            .map(Order::from)
+            .ok(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let by_hash = order_hash.and_then(|hash| {
+        candidates
+            .iter()
+            .position(|order| order.order_hash() == hash)
+    });
+
+    // a reactor can fill more than one order type, so this only decides if exactly one layout matches
+    let by_reactor = || {
+        let config = config?;
+        let mut matching = candidates.iter().enumerate().filter(|(_, order)| {
+            config
+                .order_types_of(order.reactor_address())
+                .contains(&order.order_type())
+        });
+
+        match (matching.next(), matching.next()) {
+            (Some((index, _)), None) => Some(index),
+            _ => None,
+        }
+    };
+
+    match by_hash.or_else(by_reactor) {
+        Some(index) => Ok(candidates[index].clone()),
+        None => match candidates.len() {
+            0 => Err(EncodingError::UnknownLayout),
+            1 => Ok(candidates[0].clone()),
+            _ => Err(EncodingError::AmbiguousOrderType(
+                candidates.iter().map(Order::order_type).collect(),
+            )),
+        },
+    }
+}
+
+impl SignedOrder {
+    /// the uniswap api shape of this order, including the chain id
+    pub fn to_api(&self, chain_id: u64) -> ApiSignedOrder {
+        ApiSignedOrder {
+            chain_id: Some(chain_id),
+            ..ApiSignedOrder::from(self)
+        }
+    }
+
+    /// the fully decoded shape of this order
+    pub fn to_decoded(&self) -> DecodedSignedOrder {
+        DecodedSignedOrder::from(self)
+    }
+}
+
+impl From<&SignedOrder> for ApiSignedOrder {
+    fn from(order: &SignedOrder) -> Self {
+        Self {
+            encoded_order: hex_encode(&order.encode()),
+            signature: order.sig.clone(),
+            order_type: order.order_type(),
+            chain_id: order.metadata.as_ref().and_then(|m| m.chain_id),
+            order_hash: order.order_hash().to_string(),
+        }
+    }
+}
+
+impl From<SignedOrder> for ApiSignedOrder {
+    fn from(order: SignedOrder) -> Self {
+        Self::from(&order)
+    }
+}
+
+/// the declared `type` isnt trusted, the order type is detected with [detect_order] and the built in reactors,
+/// and the order is rejected if it doesnt hash to `orderHash`
+impl TryFrom<ApiSignedOrder> for SignedOrder {
+    type Error = EncodingError;
+
+    fn try_from(order: ApiSignedOrder) -> Result<Self, Self::Error> {
+        let expected: B256 = order
+            .order_hash
+            .parse()
+            .map_err(|_| EncodingError::InvalidOrderHash(order.order_hash.clone()))?;
+
+        let config = order.chain_id.and_then(ChainConfig::builtin);
+        let inner = detect_order(&order.encoded_order, config.as_ref(), Some(expected))?;
+
+        let computed = inner.order_hash();
+        if computed != expected {
+            return Err(EncodingError::HashMismatch { expected, computed });
+        }
+
+        let metadata = order.chain_id.map(|chain_id| OrderMetadata {
+            chain_id: Some(chain_id),
+            ..Default::default()
+        });
+
+        Ok(SignedOrder {
+            metadata,
+            ..inner.signed(order.signature)
+        })
+    }
+}
+
+impl From<&SignedOrder> for DecodedSignedOrder {
+    fn from(order: &SignedOrder) -> Self {
+        Self {
+            order: DecodedOrder::from(&order.order),
+            signature: order.sig.clone(),
+            order_hash: order.order_hash().to_string(),
+            metadata: order.metadata.clone(),
+        }
+    }
+}
+
+impl TryFrom<DecodedSignedOrder> for SignedOrder {
+    type Error = EncodingError;
+
+    fn try_from(order: DecodedSignedOrder) -> Result<Self, Self::Error> {
+        Ok(SignedOrder {
+            metadata: order.metadata,
+            ..Order::try_from(order.order)?.signed(order.signature)
+        })
+    }
+}
+
+impl From<Order> for DecodedOrder {
+    fn from(order: Order) -> Self {
+        Self::from(&order)
+    }
+}
+
+impl TryFrom<DecodedOrder> for Order {
+    type Error = EncodingError;
+
+    fn try_from(order: DecodedOrder) -> Result<Self, Self::Error> {
+        Ok(match order {
+            DecodedOrder::Dutch(o) => Order::from(DutchOrder::try_from(o)?),
+            DecodedOrder::Limit(o) => Order::from(LimitOrder::try_from(o)?),
+            DecodedOrder::ExclusiveDutch(o) => Order::from(ExclusiveDutchOrder::try_from(o)?),
+        })
+    }
+}
+
+impl From<OrderInfo> for DecodedOrderInfo {
+    fn from(info: OrderInfo) -> Self {
+        Self {
+            reactor: info.reactor,
+            swapper: info.swapper,
+            nonce: info.nonce,
+            deadline: info.deadline,
+            additional_validation_contract: info.additionalValidationContract,
+            additional_validation_data: hex_encode(&info.additionalValidationData),
+        }
+    }
+}
+
+impl TryFrom<DecodedOrderInfo> for OrderInfo {
+    type Error = EncodingError;
+
+    fn try_from(info: DecodedOrderInfo) -> Result<Self, Self::Error> {
+        Ok(Self {
+            reactor: info.reactor,
+            swapper: info.swapper,
+            nonce: info.nonce,
+            deadline: info.deadline,
+            additionalValidationContract: info.additional_validation_contract,
+            additionalValidationData: hex::decode(
+                info.additional_validation_data.trim_start_matches("0x"),
+            )?,
+        })
+    }
+}
+
+impl From<DutchInput> for DecodedDutchInput {
+    fn from(input: DutchInput) -> Self {
+        Self {
+            token: input.token,
+            start_amount: input.startAmount,
+            end_amount: input.endAmount,
+        }
+    }
+}
+
+impl From<DecodedDutchInput> for DutchInput {
+    fn from(input: DecodedDutchInput) -> Self {
+        Self {
+            token: input.token,
+            startAmount: input.start_amount,
+            endAmount: input.end_amount,
+        }
+    }
+}
+
+impl From<DutchOutput> for DecodedDutchOutput {
+    fn from(output: DutchOutput) -> Self {
+        Self {
+            token: output.token,
+            start_amount: output.startAmount,
+            end_amount: output.endAmount,
+            recipient: output.recipient,
+        }
+    }
+}
+
+impl From<DecodedDutchOutput> for DutchOutput {
+    fn from(output: DecodedDutchOutput) -> Self {
+        Self {
+            token: output.token,
+            startAmount: output.start_amount,
+            endAmount: output.end_amount,
+            recipient: output.recipient,
+        }
+    }
+}
+
+impl From<InputToken> for DecodedInputToken {
+    fn from(input: InputToken) -> Self {
+        Self {
+            token: input.token,
+            amount: input.amount,
+            max_amount: input.maxAmount,
+        }
+    }
+}
+
+impl From<DecodedInputToken> for InputToken {
+    fn from(input: DecodedInputToken) -> Self {
+        Self {
+            token: input.token,
+            amount: input.amount,
+            maxAmount: input.max_amount,
+        }
+    }
+}
+
+impl From<OutputToken> for DecodedOutputToken {
+    fn from(output: OutputToken) -> Self {
+        Self {
+            token: output.token,
+            amount: output.amount,
+            recipient: output.recipient,
+        }
+    }
+}
+
+impl From<DecodedOutputToken> for OutputToken {
+    fn from(output: DecodedOutputToken) -> Self {
+        Self {
+            token: output.token,
+            amount: output.amount,
+            recipient: output.recipient,
+        }
+    }
+}
+
+impl From<DutchOrder> for DecodedDutchOrder {
+    fn from(order: DutchOrder) -> Self {
+        Self {
+            info: order.info.into(),
+            decay_start_time: order.decayStartTime,
+            decay_end_time: order.decayEndTime,
+            input: order.input.into(),
+            outputs: order.outputs.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<DecodedDutchOrder> for DutchOrder {
+    type Error = EncodingError;
+
+    fn try_from(order: DecodedDutchOrder) -> Result<Self, Self::Error> {
+        Ok(Self {
+            info: order.info.try_into()?,
+            decayStartTime: order.decay_start_time,
+            decayEndTime: order.decay_end_time,
+            input: order.input.into(),
+            outputs: order.outputs.into_iter().map(Into::into).collect(),
+        })
+    }
+}
+
+impl From<ExclusiveDutchOrder> for DecodedExclusiveDutchOrder {
+    fn from(order: ExclusiveDutchOrder) -> Self {
+        Self {
+            info: order.info.into(),
+            decay_start_time: order.decayStartTime,
+            decay_end_time: order.decayEndTime,
+            exclusive_filler: order.exclusiveFiller,
+            exclusivity_override_bps: order.exclusivityOverrideBps,
+            input: order.input.into(),
+            outputs: order.outputs.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<DecodedExclusiveDutchOrder> for ExclusiveDutchOrder {
+    type Error = EncodingError;
+
+    fn try_from(order: DecodedExclusiveDutchOrder) -> Result<Self, Self::Error> {
+        Ok(Self {
+            info: order.info.try_into()?,
+            decayStartTime: order.decay_start_time,
+            decayEndTime: order.decay_end_time,
+            exclusiveFiller: order.exclusive_filler,
+            exclusivityOverrideBps: order.exclusivity_override_bps,
+            input: order.input.into(),
+            outputs: order.outputs.into_iter().map(Into::into).collect(),
+        })
+    }
+}
+
+impl From<LimitOrder> for DecodedLimitOrder {
+    fn from(order: LimitOrder) -> Self {
+        Self {
+            info: order.info.into(),
+            input: order.input.into(),
+            outputs: order.outputs.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<DecodedLimitOrder> for LimitOrder {
+    type Error = EncodingError;
+
+    fn try_from(order: DecodedLimitOrder) -> Result<Self, Self::Error> {
+        Ok(Self {
+            info: order.info.try_into()?,
+            input: order.input.into(),
+            outputs: order.outputs.into_iter().map(Into::into).collect(),
+        })
+    }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+impl std::error::Error for EncodingError {}
+
+impl std::fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EncodingError::Abi(e) => write!(f, "Abi error: {}", e),
+            EncodingError::Hex(e) => write!(f, "Hex error: {}", e),
+            EncodingError::UnknownLayout => write!(f, "Order doesnt decode as any order type"),
+            EncodingError::AmbiguousOrderType(types) => {
+                write!(f, "Order decodes as more than one order type: {:?}", types)
+            }
+            EncodingError::InvalidOrderHash(hash) => write!(f, "Invalid order hash: {}", hash),
+            EncodingError::HashMismatch { expected, computed } => {
+                write!(
+                    f,
+                    "Hash mismatch: expected {} computed {}",
+                    expected, computed
+                )
+            }
+        }
+    }
+}
+
+impl From<alloy_sol_types::Error> for EncodingError {
+    fn from(e: alloy_sol_types::Error) -> Self {
+        EncodingError::Abi(e)
+    }
+}
+
+impl From<hex::FromHexError> for EncodingError {
+    fn from(e: hex::FromHexError) -> Self {
+        EncodingError::Hex(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn assert_same(a: &Order, b: &Order) {
+        assert_eq!(a.order_type(), b.order_type());
+        assert_eq!(a.encode(), b.encode());
+        assert_eq!(a.order_hash(), b.order_hash());
+    }
+
+    #[test]
+    fn decoded_order_round_trip() {
+        for order in orders() {
+            let json = serde_json::to_string(&order).unwrap();
+            let decoded: Order = serde_json::from_str(&json).unwrap();
+
+            assert_same(&order, &decoded);
+        }
+    }
+
+    #[test]
+    fn api_signed_order_round_trip() {
+        for order in orders() {
+            let signed = order.sign(&wallet(), &config()).unwrap();
+
+            let json = serde_json::to_string(&signed).unwrap();
+            let decoded: SignedOrder = serde_json::from_str(&json).unwrap();
+
+            assert_same(&signed.order, &decoded.order);
+            assert_eq!(signed.sig, decoded.sig);
+            assert_eq!(
+                decoded.verify(&config()),
+                crate::signing::SignatureStatus::Valid
+            );
+        }
+    }
+
+    #[test]
+    fn decoded_signed_order_round_trip() {
+        for order in orders() {
+            let signed = order
+                .sign(&wallet(), &config())
+                .unwrap()
+                .with_metadata(OrderMetadata {
+                    source: Some("test".to_string()),
+                    chain_id: Some(1),
+                    ..Default::default()
+                });
+
+            let json = serde_json::to_string(&signed.to_decoded()).unwrap();
+            let decoded =
+                SignedOrder::try_from(serde_json::from_str::<DecodedSignedOrder>(&json).unwrap())
+                    .unwrap();
+
+            assert_same(&signed.order, &decoded.order);
+            assert_eq!(signed.sig, decoded.sig);
+            assert_eq!(signed.metadata, decoded.metadata);
+        }
+    }
+
+    #[test]
+    fn api_order_type_isnt_trusted() {
+        let signed = exclusive_dutch().sign(&wallet(), &config()).unwrap();
+
+        let mut api = ApiSignedOrder::from(&signed);
+        api.order_type = OrderType::Dutch;
+
+        let decoded = SignedOrder::try_from(api).unwrap();
+        assert_eq!(decoded.order_type(), OrderType::ExclusiveDutch);
+    }
+
+    #[test]
+    fn api_order_hash_mismatch_is_rejected() {
+        let signed = dutch().sign(&wallet(), &config()).unwrap();
+
+        let mut api = ApiSignedOrder::from(&signed);
+        api.order_hash = limit().order_hash().to_string();
+
+        assert!(matches!(
+            SignedOrder::try_from(api),
+            Err(EncodingError::HashMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn detect_order_by_hash_and_reactor() {
+        for order in orders() {
+            let encoded = hex_encode(&order.encode());
+
+            let detected =
+                detect_order(&encoded, Some(&config()), Some(order.order_hash())).unwrap();
+            assert_same(&order, &detected);
+
+            let detected = detect_order(&encoded, None, Some(order.order_hash())).unwrap();
+            assert_same(&order, &detected);
+        }
+
+        // without a hash the reactor decides, e.g. a limit reactor from an override
+        let order = limit_builder()
+            .additional_validation(Address::ZERO, vec![])
+            .build()
+            .unwrap();
+
+        let detected =
+            detect_order(&hex_encode(&order.encode()), Some(&limit_config()), None).unwrap();
+        assert_same(&order, &detected);
+    }
+}
@@ -0,0 +1,329 @@
+use crate::{
+    order::{Order, OrderType},
+    utils::unix_timestamp,
+};
+use alloy_primitives::{Address, U256};
+use std::sync::Arc;
+
+/// a predicate over the fields of an [Order]
+///
+/// amounts are compared against the smaller of the start and end amounts, so they hold for the whole decay
+///
+/// ```ignore
+/// let filter = OrderFilter::InputToken(weth)
+///     .and(OrderFilter::OutputToken(usdc))
+///     .and(OrderFilter::MinInput { token: weth, amount: one_eth })
+///     .and(OrderFilter::FillableBy(me).or(!OrderFilter::OrderType(OrderType::ExclusiveDutch)));
+/// ```
+#[derive(Clone, Default)]
+pub enum OrderFilter {
+    /// every order
+    #[default]
+    Any,
+    InputToken(Address),
+    /// any of the outputs is this token
+    OutputToken(Address),
+    /// the input is `token` and at least `amount`
+    MinInput {
+        token: Address,
+        amount: U256,
+    },
+    /// the outputs in `token` add up to at least `amount`
+    MinOutput {
+        token: Address,
+        amount: U256,
+    },
+    Reactor(Address),
+    Swapper(Address),
+    /// the order has an exclusive filler and its this address
+    ExclusiveFiller(Address),
+    /// this address can fill the order, see [Order::fillable_by]
+    FillableBy(Address),
+    /// at least this many seconds until the deadline
+    MinTimeLeft(u64),
+    /// at most this many seconds until the deadline
+    MaxTimeLeft(u64),
+    OrderType(OrderType),
+    /// every filter matches, true if empty
+    AllOf(Vec<OrderFilter>),
+    /// some filter matches, false if empty
+    AnyOf(Vec<OrderFilter>),
+    Not(Box<OrderFilter>),
+    Custom(Arc<dyn Fn(&Order) -> bool + Send + Sync>),
+}
+
+impl OrderFilter {
+    /// a filter from a closure
+    pub fn custom(f: impl Fn(&Order) -> bool + Send + Sync + 'static) -> Self {
+        Self::Custom(Arc::new(f))
+    }
+
+    /// both filters match
+    pub fn and(self, other: OrderFilter) -> Self {
+        match self {
+            OrderFilter::Any => other,
+            OrderFilter::AllOf(mut filters) => {
+                filters.push(other);
+                OrderFilter::AllOf(filters)
+            }
+            filter => OrderFilter::AllOf(vec![filter, other]),
+        }
+    }
+
+    /// either filter matches
+    pub fn or(self, other: OrderFilter) -> Self {
+        match self {
+            OrderFilter::AnyOf(mut filters) => {
+                filters.push(other);
+                OrderFilter::AnyOf(filters)
+            }
+            filter => OrderFilter::AnyOf(vec![filter, other]),
+        }
+    }
+
+    /// if the order matches now
+    pub fn matches(&self, order: &Order) -> bool {
+        self.matches_at(order, unix_timestamp())
+    }
+
+    /// if the order matches at the unix timestamp `now`
+    pub fn matches_at(&self, order: &Order, now: U256) -> bool {
+        match self {
+            OrderFilter::Any => true,
+            OrderFilter::InputToken(token) => order.input().token == *token,
+            OrderFilter::OutputToken(token) => {
+                order.outputs().iter().any(|output| output.token == *token)
+            }
+            OrderFilter::MinInput { token, amount } => {
+                let input = order.input();
+                input.token == *token && input.startAmount.min(input.endAmount) >= *amount
+            }
+            OrderFilter::MinOutput { token, amount } => {
+                let total = order
+                    .outputs()
+                    .iter()
+                    .filter(|output| output.token == *token)
+                    .fold(U256::ZERO, |total, output| {
+                        total.saturating_add(output.startAmount.min(output.endAmount))
+                    });
+
+                total >= *amount
+            }
+            OrderFilter::Reactor(reactor) => order.reactor_address() == *reactor,
+            OrderFilter::Swapper(swapper) => order.swapper() == *swapper,
+            OrderFilter::ExclusiveFiller(filler) => order.exclusive_filler() == Some(*filler),
+            OrderFilter::FillableBy(filler) => order.fillable_by(*filler, now),
+            OrderFilter::MinTimeLeft(secs) => {
+                order.deadline() >= now.saturating_add(U256::from(*secs))
+            }
+            OrderFilter::MaxTimeLeft(secs) => {
+                order.deadline() <= now.saturating_add(U256::from(*secs))
+            }
+            OrderFilter::OrderType(order_type) => order.order_type() == *order_type,
+            OrderFilter::AllOf(filters) => filters.iter().all(|f| f.matches_at(order, now)),
+            OrderFilter::AnyOf(filters) => filters.iter().any(|f| f.matches_at(order, now)),
+            OrderFilter::Not(filter) => !filter.matches_at(order, now),
+            OrderFilter::Custom(f) => f(order),
+        }
+    }
+}
+
+impl std::ops::Not for OrderFilter {
+    type Output = OrderFilter;
+
+    fn not(self) -> Self::Output {
+        match self {
+            OrderFilter::Not(filter) => *filter,
+            filter => OrderFilter::Not(Box::new(filter)),
+        }
+    }
+}
+
+impl std::fmt::Debug for OrderFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderFilter::Any => write!(f, "Any"),
+            OrderFilter::InputToken(token) => write!(f, "InputToken({})", token),
+            OrderFilter::OutputToken(token) => write!(f, "OutputToken({})", token),
+            OrderFilter::MinInput { token, amount } => {
+                write!(f, "MinInput {{ token: {}, amount: {} }}", token, amount)
+            }
+            OrderFilter::MinOutput { token, amount } => {
+                write!(f, "MinOutput {{ token: {}, amount: {} }}", token, amount)
+            }
+            OrderFilter::Reactor(reactor) => write!(f, "Reactor({})", reactor),
+            OrderFilter::Swapper(swapper) => write!(f, "Swapper({})", swapper),
+            OrderFilter::ExclusiveFiller(filler) => write!(f, "ExclusiveFiller({})", filler),
+            OrderFilter::FillableBy(filler) => write!(f, "FillableBy({})", filler),
+            OrderFilter::MinTimeLeft(secs) => write!(f, "MinTimeLeft({})", secs),
+            OrderFilter::MaxTimeLeft(secs) => write!(f, "MaxTimeLeft({})", secs),
+            OrderFilter::OrderType(order_type) => write!(f, "OrderType({:?})", order_type),
+            OrderFilter::AllOf(filters) => f.debug_tuple("AllOf").field(filters).finish(),
+            OrderFilter::AnyOf(filters) => f.debug_tuple("AnyOf").field(filters).finish(),
+            OrderFilter::Not(filter) => f.debug_tuple("Not").field(filter).finish(),
+            OrderFilter::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{contracts::internal::builder::DutchOrderBuilder, test_utils::*};
+
+    fn exclusive_dutch(override_bps: u64) -> Order {
+        exclusive_dutch_builder()
+            .exclusivity(EXCLUSIVE_FILLER, u(override_bps))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn fillable_by_respects_exclusivity() {
+        let order = exclusive_dutch(0);
+        let exclusive = OrderFilter::FillableBy(EXCLUSIVE_FILLER);
+        let other = OrderFilter::FillableBy(Address::repeat_byte(8));
+
+        assert!(exclusive.matches_at(&order, U256::from(500)));
+        assert!(!other.matches_at(&order, U256::from(500)));
+        assert!(!other.matches_at(&order, U256::from(1000)));
+        // the exclusivity ends at the decay start time
+        assert!(other.matches_at(&order, U256::from(1001)));
+    }
+
+    #[test]
+    fn fillable_by_with_an_override() {
+        let order = exclusive_dutch(100);
+        let other = OrderFilter::FillableBy(Address::repeat_byte(8));
+
+        assert!(other.matches_at(&order, U256::from(500)));
+    }
+
+    fn matches(filter: &OrderFilter) -> bool {
+        filter.matches_at(&dutch(), U256::from(1500))
+    }
+
+    #[test]
+    fn composition() {
+        let yes = OrderFilter::InputToken(INPUT_TOKEN);
+        let no = OrderFilter::InputToken(OUTPUT_TOKEN);
+
+        assert!(matches(&OrderFilter::Any));
+        assert!(matches(&OrderFilter::AllOf(vec![])));
+        assert!(!matches(&OrderFilter::AnyOf(vec![])));
+
+        assert!(matches(&yes.clone().and(yes.clone())));
+        assert!(!matches(&yes.clone().and(no.clone())));
+        assert!(!matches(&no.clone().and(yes.clone())));
+        assert!(matches(&yes.clone().or(no.clone())));
+        assert!(matches(&no.clone().or(yes.clone())));
+        assert!(!matches(&no.clone().or(no.clone())));
+        assert!(!matches(&!yes.clone()));
+        assert!(matches(&!no.clone()));
+
+        // (no or yes) and not (no and yes)
+        let nested = no.clone().or(yes.clone()).and(!no.clone().and(yes.clone()));
+        assert!(matches(&nested));
+        assert!(!matches(&!nested));
+    }
+
+    #[test]
+    fn composition_flattens() {
+        let a = OrderFilter::InputToken(INPUT_TOKEN);
+        let b = OrderFilter::OutputToken(OUTPUT_TOKEN);
+        let c = OrderFilter::Swapper(swapper());
+
+        assert!(matches!(
+            OrderFilter::Any.and(a.clone()),
+            OrderFilter::InputToken(_)
+        ));
+        assert!(matches!(
+            a.clone().and(b.clone()).and(c.clone()),
+            OrderFilter::AllOf(filters) if filters.len() == 3
+        ));
+        assert!(matches!(
+            a.clone().or(b.clone()).or(c.clone()),
+            OrderFilter::AnyOf(filters) if filters.len() == 3
+        ));
+        // or keeps the Any, it still matches everything
+        assert!(matches!(
+            OrderFilter::Any.or(a.clone()),
+            OrderFilter::AnyOf(filters) if filters.len() == 2
+        ));
+        assert!(matches!(!!a, OrderFilter::InputToken(_)));
+    }
+
+    #[test]
+    fn tokens() {
+        assert!(matches(&OrderFilter::InputToken(INPUT_TOKEN)));
+        assert!(!matches(&OrderFilter::InputToken(OUTPUT_TOKEN)));
+        assert!(matches(&OrderFilter::OutputToken(OUTPUT_TOKEN)));
+        assert!(!matches(&OrderFilter::OutputToken(INPUT_TOKEN)));
+
+        let order = dutch_builder()
+            .output(INPUT_TOKEN, u(10), u(10), swapper())
+            .build()
+            .unwrap();
+        assert!(OrderFilter::OutputToken(INPUT_TOKEN).matches_at(&order, u(1500)));
+        assert!(OrderFilter::OutputToken(OUTPUT_TOKEN).matches_at(&order, u(1500)));
+    }
+
+    #[test]
+    fn amounts_hold_for_the_whole_decay() {
+        let min_input = |token, amount| OrderFilter::MinInput {
+            token,
+            amount: u(amount),
+        };
+        let min_output = |token, amount| OrderFilter::MinOutput {
+            token,
+            amount: u(amount),
+        };
+
+        assert!(matches(&min_input(INPUT_TOKEN, 100)));
+        assert!(!matches(&min_input(INPUT_TOKEN, 101)));
+        assert!(!matches(&min_input(OUTPUT_TOKEN, 0)));
+
+        // the output decays from 300 to 200
+        assert!(matches(&min_output(OUTPUT_TOKEN, 200)));
+        assert!(!matches(&min_output(OUTPUT_TOKEN, 201)));
+        assert!(!matches(&min_output(INPUT_TOKEN, 1)));
+        assert!(matches(&min_output(INPUT_TOKEN, 0)));
+
+        // outputs in the same token add up
+        let order = dutch_builder()
+            .output(OUTPUT_TOKEN, u(100), u(50), Address::repeat_byte(8))
+            .build()
+            .unwrap();
+        assert!(min_output(OUTPUT_TOKEN, 250).matches_at(&order, u(1500)));
+        assert!(!min_output(OUTPUT_TOKEN, 251).matches_at(&order, u(1500)));
+
+        // an exact output order, the input decays up from 100 to 150
+        let order = DutchOrderBuilder::new()
+            .reactor(config().reactor(OrderType::Dutch).unwrap())
+            .swapper(swapper())
+            .nonce(u(1))
+            .deadline(u(2000))
+            .decay_start_time(u(1000))
+            .decay_end_time(u(2000))
+            .input(INPUT_TOKEN, u(100), u(150))
+            .output(OUTPUT_TOKEN, u(300), u(300), swapper())
+            .build()
+            .unwrap();
+        assert!(min_input(INPUT_TOKEN, 100).matches_at(&order, u(1500)));
+        assert!(!min_input(INPUT_TOKEN, 101).matches_at(&order, u(1500)));
+    }
+
+    #[test]
+    fn time_left_is_measured_to_the_deadline() {
+        let order = dutch();
+
+        // the deadline is 2000
+        assert!(OrderFilter::MinTimeLeft(500).matches_at(&order, u(1500)));
+        assert!(!OrderFilter::MinTimeLeft(500).matches_at(&order, u(1501)));
+        assert!(OrderFilter::MaxTimeLeft(500).matches_at(&order, u(1500)));
+        assert!(!OrderFilter::MaxTimeLeft(500).matches_at(&order, u(1499)));
+
+        assert!(!OrderFilter::MinTimeLeft(u64::MAX).matches_at(&order, u(0)));
+        assert!(OrderFilter::MaxTimeLeft(0).matches_at(&order, u(2001)));
+    }
+}
@@ -1,6 +1,14 @@
 /// contains the public and internal contract types derived from the alloy [sol!] macro
 pub mod contracts;
 
+/// per chain contract addresses, see [config::AddressBook]
+pub mod config;
+
+/// serde shapes of orders, the uniswap api shape and a fully decoded one
+///
+/// see [encoding::ApiSignedOrder] and [encoding::DecodedSignedOrder]
+pub mod encoding;
+
 /// the core types of the sdk
 /// implements the verification and quote logic
 ///
@@ -8,6 +16,34 @@ pub mod contracts;
 /// also you can find some helper function on the [Order] type as well
 pub mod order;
 
+/// [filter::OrderFilter], composable predicates over the fields of an order
+pub mod filter;
+
+/// offline resolution of orders, ports of the `DutchDecayLib` and `ExclusivityOverrideLib` logic from the reactors
+///
+/// see [order::Order::resolve_at]
+pub mod resolver;
+
+/// typed decoding of the revert data from the reactors, permit2 and the order quoter
+///
+/// see [revert::RevertReason::decode]
+pub mod revert;
+
+/// eip712 order hashing and permit2 witness signing
+///
+/// see [order::Order::permit2_digest] and [order::Order::sign]
+pub mod signing;
+
+/// order validation without the order quoter
+///
+/// see [order::Order::validate_offline] for checks that need no chain state,
+/// and [order::SignedOrder::validate_state_ethers] for the swappers balance, allowance and nonce
+pub mod validation;
+
+/// shared order fixtures for tests, enabled for other crates with the `test-utils` feature
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
 /// this is where [OrderCache] is implemented,
 /// there is also some useful helper functions for working with tokio
 pub mod utils;
@@ -1,21 +1,50 @@
-use crate::contracts::{
-    common::{OrderInfo, ResolvedOrder},
-    internal::{dutch::DutchOrder, exclusive_dutch::ExclusiveDutchOrder, limit::LimitOrder},
+use crate::{
+    config::ChainConfig,
+    contracts::{
+        common::{OrderInfo, ResolvedOrder},
+        internal::{
+            dutch::{DutchInput, DutchOrder, DutchOutput},
+            exclusive_dutch::ExclusiveDutchOrder,
+            limit::LimitOrder,
+        },
+    },
+    encoding::{ApiSignedOrder, DecodedOrder},
+    resolver::{ResolutionError, Resolve},
+    revert::{into_alloy_u256, into_ethers_address, into_ethers_u256, RevertReason},
+    signing::{
+        is_valid_signature_calldata, parse_signature, permit2_address, permit2_domain,
+        permit_witness_digest, Permit2Witness, SignatureStatus, EIP1271_MAGIC_VALUE,
+    },
+    validation::{
+        common_checks, nonce_bitmap_position, OfflineCheck, OfflineValidation, StateReport,
+        ValidationReport,
+    },
 };
 use alloy_primitives::{Address, B256, U256};
 use alloy_sol_types::{SolStruct, SolType};
 
 use ethers::{
     abi::AbiEncode,
-    contract::ContractError,
+    contract::{ContractError, Multicall, MulticallError},
     prelude::ContractCall,
     providers::Middleware,
-    types::{Address as EthersAddress, ParseBytesError},
+    signers::{LocalWallet, WalletError},
+    types::{
+        transaction::eip2718::TypedTransaction, Address as EthersAddress, Bytes, ParseBytesError,
+        SignatureError, TransactionRequest, H256,
+    },
 };
-use serde::Deserialize;
-use std::{ops::Deref, sync::Arc};
-use uniswapx_ethers_bindings::order_quoter::order_quoter::{
-    OrderQuoter, ResolvedOrder as EthersResolvedOrder,
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    ops::Deref,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use uniswapx_ethers_bindings::{
+    erc20::ERC20,
+    i_permit_2::IPermit2,
+    order_quoter::order_quoter::{OrderQuoter, ResolvedOrder as EthersResolvedOrder},
 };
 
 #[allow(unused_imports)]
@@ -26,7 +55,10 @@ use tracing::{debug, error, info, trace, warn};
 ///
 /// in order for an implementor of of `Client<Order>` to use this type, they should have the hex encoded order and create the alloy type
 /// the you can create the order inner, and subsequently the order
-#[derive(Clone)]
+///
+/// serializes as a [DecodedOrder]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(into = "DecodedOrder", try_from = "DecodedOrder")]
 pub struct Order {
     inner: OrderInner,
 }
@@ -39,15 +71,39 @@ enum OrderInner {
     ExclusiveDutch(ExclusiveDutchOrder),
 }
 
-#[derive(Clone)]
+/// serializes in the uniswap api shape, see [ApiSignedOrder] and [crate::encoding::decoded]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(into = "ApiSignedOrder", try_from = "ApiSignedOrder")]
 pub struct SignedOrder {
     pub order: Order,
     pub sig: String,
+    /// where and when this order was received, if its known
+    pub metadata: Option<OrderMetadata>,
+}
+
+/// information about a [SignedOrder] that isnt part of the signed order itself
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderMetadata {
+    /// the client the order came from, e.g. `uniswap`
+    pub source: Option<String>,
+    /// when the order was received from the source
+    pub received_at: Option<SystemTime>,
+    /// the unix timestamp in seconds the source says the order was created at
+    pub created_at: Option<u64>,
+    /// the status the source last reported for the order
+    pub status: Option<String>,
+    pub chain_id: Option<u64>,
+    /// the order hash as reported by the source, see [Order::order_hash] for the computed hash
+    pub order_hash: Option<String>,
+    /// any other fields the source returned, e.g. `quoteId` or `requestId`
+    #[serde(default)]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// https://github.com/Uniswap/uniswapx-sdk/blob/main/src/constants.ts
 /// only used for deriving our types from external api calls
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum OrderType {
     Dutch,
     Limit,
@@ -57,6 +113,7 @@ pub enum OrderType {
 #[derive(Debug)]
 pub enum ValidationError<M: Middleware> {
     ContractError(ContractError<M>),
+    MulticallError(MulticallError<M>),
     SigParseError(ParseBytesError),
 }
 
@@ -107,6 +164,30 @@ impl OrderInner {
         }
     }
 
+    fn order_hash(&self) -> B256 {
+        match self {
+            OrderInner::Dutch(o) => o.order_hash(),
+            OrderInner::Limit(o) => o.order_hash(),
+            OrderInner::ExclusiveDutch(o) => o.order_hash(),
+        }
+    }
+
+    fn permitted(&self) -> (Address, U256) {
+        match self {
+            OrderInner::Dutch(o) => o.permitted(),
+            OrderInner::Limit(o) => o.permitted(),
+            OrderInner::ExclusiveDutch(o) => o.permitted(),
+        }
+    }
+
+    fn witness_type_string(&self) -> String {
+        match self {
+            OrderInner::Dutch(_) => DutchOrder::witness_type_string(),
+            OrderInner::Limit(_) => LimitOrder::witness_type_string(),
+            OrderInner::ExclusiveDutch(_) => ExclusiveDutchOrder::witness_type_string(),
+        }
+    }
+
     fn order_type(&self) -> OrderType {
         match self {
             OrderInner::Dutch(_) => OrderType::Dutch,
@@ -114,73 +195,316 @@ impl OrderInner {
             OrderInner::ExclusiveDutch(_) => OrderType::ExclusiveDutch,
         }
     }
+
+    fn resolve_at(&self, timestamp: U256) -> Result<ResolvedOrder, ResolutionError> {
+        match self {
+            OrderInner::Dutch(o) => o.resolve_at(timestamp),
+            OrderInner::Limit(o) => o.resolve_at(timestamp),
+            OrderInner::ExclusiveDutch(o) => o.resolve_at(timestamp),
+        }
+    }
+
+    fn resolve_for(
+        &self,
+        filler: Address,
+        timestamp: U256,
+    ) -> Result<ResolvedOrder, ResolutionError> {
+        match self {
+            OrderInner::Dutch(o) => o.resolve_for(filler, timestamp),
+            OrderInner::Limit(o) => o.resolve_for(filler, timestamp),
+            OrderInner::ExclusiveDutch(o) => o.resolve_for(filler, timestamp),
+        }
+    }
+
+    fn input(&self) -> DutchInput {
+        match self {
+            OrderInner::Dutch(o) => o.input.clone(),
+            OrderInner::Limit(o) => DutchInput {
+                token: o.input.token,
+                startAmount: o.input.amount,
+                endAmount: o.input.amount,
+            },
+            OrderInner::ExclusiveDutch(o) => o.input.clone(),
+        }
+    }
+
+    fn outputs(&self) -> Vec<DutchOutput> {
+        match self {
+            OrderInner::Dutch(o) => o.outputs.clone(),
+            OrderInner::Limit(o) => o
+                .outputs
+                .iter()
+                .map(|output| DutchOutput {
+                    token: output.token,
+                    startAmount: output.amount,
+                    endAmount: output.amount,
+                    recipient: output.recipient,
+                })
+                .collect(),
+            OrderInner::ExclusiveDutch(o) => o.outputs.clone(),
+        }
+    }
+
+    fn offline_checks(&self) -> Vec<OfflineCheck> {
+        match self {
+            OrderInner::Dutch(o) => o.offline_checks(),
+            OrderInner::Limit(o) => o.offline_checks(),
+            OrderInner::ExclusiveDutch(o) => o.offline_checks(),
+        }
+    }
 }
 
 /// see https://github.com/Uniswap/uniswapx-sdk/blob/01b4516bde998503ee01555644e3711cb36892c9/src/utils/OrderQuoter.ts#L45
-impl From<String> for ValidationStatus {
-    fn from(s: String) -> Self {
-        match s.as_str() {
-            "0x302e5b7c" | "0x773a6187" => {
+impl From<RevertReason> for ValidationStatus {
+    fn from(reason: RevertReason) -> Self {
+        match reason {
+            RevertReason::DeadlineBeforeEndTime
+            | RevertReason::EndTimeBeforeStartTime
+            | RevertReason::OrderEndTimeBeforeStartTime => {
                 info!("invalid dutch decay time");
                 ValidationStatus::InvalidOrderFields
             }
-            "0x4ddf4a64" => {
+            RevertReason::InvalidReactor => {
                 info!("invalid reactor");
                 ValidationStatus::InvalidOrderFields
             }
-            "0xd303758b" => {
+            RevertReason::InputAndOutputDecay => {
                 info!("both dutch input and output decay bad");
                 ValidationStatus::InvalidOrderFields
             }
-            "0x7c1f8113" => {
+            RevertReason::IncorrectAmounts => {
                 info!("incorrect amounts");
                 ValidationStatus::InvalidOrderFields
             }
-            "0x43133453" => {
-                info!("invalid dutch decay time");
-                ValidationStatus::InvalidOrderFields
+            RevertReason::OrdersLengthIncorrect
+            | RevertReason::DuplicateFeeOutput { .. }
+            | RevertReason::FeeTooLarge { .. }
+            | RevertReason::InvalidFeeToken { .. }
+            | RevertReason::InvalidAmount { .. }
+            | RevertReason::LengthMismatch => ValidationStatus::InvalidOrderFields,
+            RevertReason::NoExclusiveOverride | RevertReason::NotExclusiveFiller { .. } => {
+                ValidationStatus::ExclusivityPeriod
+            }
+            RevertReason::InvalidSignature
+            | RevertReason::InvalidSigner
+            | RevertReason::InvalidSignatureLength
+            | RevertReason::InvalidContractSignature => ValidationStatus::InvalidSignature,
+            RevertReason::DeadlinePassed | RevertReason::SignatureExpired { .. } => {
+                ValidationStatus::Expired
             }
-            "0xb9ec1e96" | "0x062dec56" | "0x75c1bb14" => ValidationStatus::ExclusivityPeriod,
-            "0x8baa579f" | "0x815e1d64" => ValidationStatus::InvalidSignature,
-            "0x48fee69c" => ValidationStatus::InvalidOrderFields,
-            "0x70f65caa" => ValidationStatus::Expired,
-            "0xee3b3d4b" => ValidationStatus::NonceUsed,
-            "0x0a0b0d79" => ValidationStatus::ValidationFailed,
-            "0x756688fe" => ValidationStatus::NonceUsed,
-            // transfer from? => ValidationStatus::InsufficientFunds,
-            _ => ValidationStatus::UnknownError(s),
+            RevertReason::InvalidNonce | RevertReason::ExcessiveInvalidation => {
+                ValidationStatus::NonceUsed
+            }
+            RevertReason::ValidationFailed => ValidationStatus::ValidationFailed,
+            RevertReason::TransferFromFailed
+            | RevertReason::InsufficientAllowance { .. }
+            | RevertReason::AllowanceExpired { .. } => ValidationStatus::InsufficientFunds,
+            // selectors the ts sdk knows about that arent in the bindings
+            RevertReason::Unknown(_) => match reason.selector().as_deref() {
+                Some("0x302e5b7c") => ValidationStatus::InvalidOrderFields,
+                Some("0x062dec56") => ValidationStatus::ExclusivityPeriod,
+                Some("0xee3b3d4b") => ValidationStatus::NonceUsed,
+                _ => ValidationStatus::UnknownError(reason.to_string()),
+            },
+            RevertReason::InsufficientEth
+            | RevertReason::InsufficientOutput { .. }
+            | RevertReason::NativeTransferFailed
+            | RevertReason::RevertString(_)
+            | RevertReason::Panic { .. } => ValidationStatus::UnknownError(reason.to_string()),
         }
     }
 }
 
+impl OrderMetadata {
+    /// the time between the source creating the order and it being received
+    pub fn latency(&self) -> Option<Duration> {
+        let created_at = UNIX_EPOCH + Duration::from_secs(self.created_at?);
+
+        self.received_at?.duration_since(created_at).ok()
+    }
+
+    /// an extra field the source returned
+    pub fn extra(&self, key: &str) -> Option<&serde_json::Value> {
+        self.extra.get(key)
+    }
+}
+
 impl SignedOrder {
-    /// use an ethers client to validate the order
+    /// attach [OrderMetadata] to this order
+    pub fn with_metadata(mut self, metadata: OrderMetadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// recover the signer of the permit2 witness digest, against the permit2 deployment in `config`
+    ///
+    /// this will only be the swapper for EOAs, see [SignedOrder::verify]
+    pub fn recover_signer(&self, config: &ChainConfig) -> Result<Address, SignatureError> {
+        self.recover_signer_inner(config.chain_id, config.permit2)
+    }
+
+    fn recover_signer_inner(
+        &self,
+        chain_id: u64,
+        permit2: Address,
+    ) -> Result<Address, SignatureError> {
+        let digest = self.order.permit2_digest(chain_id, permit2);
+        let signer = parse_signature(&self.sig)?.recover(H256::from(digest.0))?;
+
+        Ok(Address::from(signer.0))
+    }
+
+    /// check the signature against the swapper without any rpc calls
+    ///
+    /// a signature that doesnt recover to the swapper may still be valid if the swapper is a contract,
+    /// see [SignedOrder::verify_ethers] to detect EIP-1271 signers
+    pub fn verify(&self, config: &ChainConfig) -> SignatureStatus {
+        self.verify_inner(config.chain_id, config.permit2)
+    }
+
+    fn verify_inner(&self, chain_id: u64, permit2: Address) -> SignatureStatus {
+        match self.recover_signer_inner(chain_id, permit2) {
+            Ok(signer) if signer == self.info().swapper => SignatureStatus::Valid,
+            Ok(signer) => SignatureStatus::SignerMismatch(signer),
+            Err(_) => SignatureStatus::Malformed,
+        }
+    }
+
+    /// like [Order::validate_offline], but also checks the signature recovers to the swapper
+    ///
+    /// the signature is checked against the permit2 deployment in [ChainConfig::builtin],
+    /// or the canonical one if there is no built in config for `chain_id`
+    ///
+    /// contract swappers will always fail the signature check, see [SignedOrder::verify_ethers]
+    pub fn validate_offline(&self, now: U256, chain_id: u64) -> ValidationReport {
+        match ChainConfig::builtin(chain_id) {
+            Some(config) => self.validate_offline_with(now, &config),
+            None => {
+                let mut report = self.order.validate_offline(now, chain_id);
+                push_signature(&mut report, self.verify_inner(chain_id, permit2_address()));
+                report
+            }
+        }
+    }
+
+    /// like [SignedOrder::validate_offline], but checks the reactor and signature against the deployments in `config`
+    pub fn validate_offline_with(&self, now: U256, config: &ChainConfig) -> ValidationReport {
+        let mut report = self.order.validate_offline_with(now, config);
+        push_signature(&mut report, self.verify(config));
+        report
+    }
+
+    /// like [SignedOrder::verify], but checks if the swapper has code when the signature doesnt recover to it
+    ///
+    /// permit2 uses EIP-1271 for any swapper with code, so for contract swappers this calls `isValidSignature`
+    /// with the permit2 digest, returning [SignatureStatus::Valid] only if it returns the magic value
+    pub async fn verify_ethers<M: Middleware + 'static>(
+        &self,
+        middleware: Arc<M>,
+        config: &ChainConfig,
+    ) -> Result<SignatureStatus, ValidationError<M>> {
+        let status = self.verify(config);
+
+        if status == SignatureStatus::Valid {
+            return Ok(status);
+        }
+
+        let swapper = into_ethers_address(self.info().swapper);
+        let code = middleware
+            .get_code(swapper, None)
+            .await
+            .map_err(ContractError::from_middleware_error)?;
+
+        if code.is_empty() {
+            return Ok(status);
+        }
+
+        let digest = self.order.permit2_digest(config.chain_id, config.permit2);
+        let sig: Bytes = self.sig.parse()?;
+        let tx: TypedTransaction = TransactionRequest::new()
+            .to(swapper)
+            .data(is_valid_signature_calldata(digest, &sig))
+            .into();
+
+        match middleware.call(&tx, None).await {
+            Ok(res) if res.len() >= 32 && res[..4] == EIP1271_MAGIC_VALUE => {
+                Ok(SignatureStatus::Valid)
+            }
+            Ok(_) => Ok(SignatureStatus::InvalidContractSignature),
+            Err(e) => match ContractError::from_middleware_error(e) {
+                // permit2 reverts with the swappers revert too
+                ContractError::Revert(_) => Ok(SignatureStatus::InvalidContractSignature),
+                e => Err(e.into()),
+            },
+        }
+    }
+
+    /// use an ethers client to validate the order against the [OrderQuoter] in `config`
     pub async fn validate_ethers<M: Middleware + 'static>(
         &self,
         middleware: Arc<M>,
+        config: &ChainConfig,
     ) -> Result<ValidationStatus, ValidationError<M>> {
-        match self
-            .quote_ethers(middleware, self.order.quoter_address())
-            .await
-        {
+        match self.quote_ethers(middleware, config).await {
             Ok(_) => Ok(ValidationStatus::OK),
             Err(ValidationError::ContractError(ContractError::Revert(bytes))) => {
-                Ok(ValidationStatus::from(bytes.to_string()))
+                Ok(ValidationStatus::from(RevertReason::decode(&bytes)))
             }
             Err(err) => Err(err),
         }
     }
 
-    /// use an ethers client to resolve and quote the order
+    /// use an ethers client to check the swappers input token balance, erc20 allowance to permit2
+    /// and permit2 nonce bitmap, batched into a single call to the multicall in `config`
+    ///
+    /// the order quoter cant tell these apart, see [SignedOrder::validate_ethers]
+    pub async fn validate_state_ethers<M: Middleware + 'static>(
+        &self,
+        middleware: Arc<M>,
+        config: &ChainConfig,
+    ) -> Result<StateReport, ValidationError<M>> {
+        let info = self.info();
+        let (token, required) = self.order.inner.permitted();
+        let swapper = into_ethers_address(info.swapper);
+        let (word_pos, mask) = nonce_bitmap_position(info.nonce);
+
+        let erc20 = ERC20::new(into_ethers_address(token), middleware.clone());
+        let permit2 = IPermit2::new(into_ethers_address(config.permit2), middleware.clone());
+
+        let mut multicall =
+            Multicall::new(middleware, Some(into_ethers_address(config.multicall))).await?;
+        multicall
+            .add_call(erc20.balance_of(swapper), false)
+            .add_call(
+                erc20.allowance(swapper, into_ethers_address(config.permit2)),
+                false,
+            )
+            .add_call(
+                permit2.nonce_bitmap(swapper, into_ethers_u256(word_pos)),
+                false,
+            );
+
+        let (balance, allowance, bitmap) = multicall.call().await?;
+
+        Ok(StateReport {
+            required,
+            balance: into_alloy_u256(balance),
+            allowance: into_alloy_u256(allowance),
+            nonce_used: into_alloy_u256(bitmap) & mask != U256::ZERO,
+        })
+    }
+
+    /// use an ethers client to resolve and quote the order with the [OrderQuoter] in `config`
     pub async fn quote_ethers<M: Middleware + 'static>(
         &self,
         middleware: Arc<M>,
-        quoter_address: Address,
+        config: &ChainConfig,
     ) -> Result<ResolvedOrder, ValidationError<M>> {
         Ok(into_alloy_resolved_order(
             self.quote_contract_call(
                 middleware,
-                quoter_address
+                config
+                    .order_quoter
                     .to_string()
                     .parse::<EthersAddress>()
                     .expect("alloy type to parse by ethers"),
@@ -200,9 +524,19 @@ impl SignedOrder {
     }
 }
 
+fn push_signature(report: &mut ValidationReport, status: SignatureStatus) {
+    if status != SignatureStatus::Valid {
+        report.failures.push(OfflineCheck::InvalidSignature(status));
+    }
+}
+
 impl Order {
     pub fn signed(self, sig: String) -> SignedOrder {
-        SignedOrder { order: self, sig }
+        SignedOrder {
+            order: self,
+            sig,
+            metadata: None,
+        }
     }
 
     pub fn info(&self) -> &OrderInfo {
@@ -217,6 +551,40 @@ impl Order {
         self.inner.type_hash()
     }
 
+    /// the order hash as computed by the reactor, this is what the swapper signs as the permit2 witness
+    ///
+    /// note: this is not the same as [Order::struct_hash]
+    pub fn order_hash(&self) -> B256 {
+        self.inner.order_hash()
+    }
+
+    /// the permit2 `PermitWitnessTransferFrom` digest the swapper signs
+    pub fn permit2_digest(&self, chain_id: u64, permit2: Address) -> B256 {
+        let info = self.info();
+
+        permit_witness_digest(
+            &permit2_domain(chain_id, permit2),
+            self.inner.permitted(),
+            info.reactor,
+            info.nonce,
+            info.deadline,
+            self.order_hash(),
+            &self.inner.witness_type_string(),
+        )
+    }
+
+    /// sign the order with a local key against the permit2 deployment in `config`
+    pub fn sign(
+        self,
+        wallet: &LocalWallet,
+        config: &ChainConfig,
+    ) -> Result<SignedOrder, WalletError> {
+        let digest = self.permit2_digest(config.chain_id, config.permit2);
+        let sig = wallet.sign_hash(H256::from(digest.0))?;
+
+        Ok(self.signed(format!("0x{}", hex::encode(sig.to_vec()))))
+    }
+
     pub fn order_type(&self) -> OrderType {
         self.inner.order_type()
     }
@@ -229,17 +597,93 @@ impl Order {
         self.inner.encode()
     }
 
+    /// resolve the order offline at some block timestamp, without a call to the [OrderQuoter]
+    ///
+    /// mirrors the reactors `resolve`, so the amounts match what [SignedOrder::quote_ethers] would return
+    ///
+    /// note: the `sig` of the returned [ResolvedOrder] is empty
+    pub fn resolve_at(&self, timestamp: U256) -> Result<ResolvedOrder, ResolutionError> {
+        let mut resolved = self.inner.resolve_at(timestamp)?;
+        resolved.hash = self.order_hash().0;
+
+        Ok(resolved)
+    }
+
+    /// resolve the outputs `filler` would actually owe if they filled at `timestamp`
+    ///
+    /// for an [ExclusiveDutchOrder] in its exclusivity period, a non exclusive filler owes the outputs
+    /// scaled up by `exclusivityOverrideBps`, or gets [ResolutionError::NoExclusiveOverride] if the override is zero
+    pub fn resolve_for(
+        &self,
+        filler: Address,
+        timestamp: U256,
+    ) -> Result<ResolvedOrder, ResolutionError> {
+        let mut resolved = self.inner.resolve_for(filler, timestamp)?;
+        resolved.hash = self.order_hash().0;
+
+        Ok(resolved)
+    }
+
+    /// run every check that doesnt need chain state, collecting all the failures
+    ///
+    /// the reactor is checked against the built in deployments for `chain_id`, see [ChainConfig::builtin],
+    /// if theres no built in reactor for the order type its only checked against the other reactors
+    ///
+    /// useful to drop bad orders before spending an rpc call on [SignedOrder::validate_ethers]
+    pub fn validate_offline(&self, now: U256, chain_id: u64) -> ValidationReport {
+        self.validate_offline_inner(now, ChainConfig::builtin(chain_id).as_ref())
+    }
+
+    /// like [Order::validate_offline], but checks the reactor against the deployments in `config`
+    pub fn validate_offline_with(&self, now: U256, config: &ChainConfig) -> ValidationReport {
+        self.validate_offline_inner(now, Some(config))
+    }
+
+    fn validate_offline_inner(&self, now: U256, config: Option<&ChainConfig>) -> ValidationReport {
+        let mut failures = common_checks(self, now, config);
+        failures.extend(self.inner.offline_checks());
+
+        ValidationReport { failures }
+    }
+
     pub fn reactor_address(&self) -> Address {
         self.info().reactor
     }
 
-    /// warning! only returns the exclusive dutch reactor address
-    pub fn quoter_address(&self) -> Address {
-        // todo should be a mapping, but api only one address on doc site
-        // see: https://docs.uniswap.org/contracts/uniswapx/overview
-        "0x7714520f383C998e8822E8743FD6f90A2979689b"
-            .parse()
-            .expect("quoter address to parse")
+    pub fn swapper(&self) -> Address {
+        self.info().swapper
+    }
+
+    /// the input before decay, limit orders dont decay so their start and end amounts are equal
+    pub fn input(&self) -> DutchInput {
+        self.inner.input()
+    }
+
+    /// the outputs before decay, limit orders dont decay so their start and end amounts are equal
+    pub fn outputs(&self) -> Vec<DutchOutput> {
+        self.inner.outputs()
+    }
+
+    /// the exclusive filler of an [ExclusiveDutchOrder], `None` for other orders or if its the zero address
+    pub fn exclusive_filler(&self) -> Option<Address> {
+        match &self.inner {
+            OrderInner::ExclusiveDutch(o) if o.exclusiveFiller != Address::ZERO => {
+                Some(o.exclusiveFiller)
+            }
+            _ => None,
+        }
+    }
+
+    /// if `filler` can fill the order at `timestamp`, either with filling rights,
+    /// i.e. theres no exclusive filler, its `filler` or the exclusivity has ended,
+    /// or by paying the exclusivity override, see [crate::resolver::handle_override]
+    pub fn fillable_by(&self, filler: Address, timestamp: U256) -> bool {
+        match &self.inner {
+            OrderInner::ExclusiveDutch(o) => {
+                o.exclusivityOverrideBps != U256::ZERO || o.has_filling_rights(filler, timestamp)
+            }
+            _ => true,
+        }
     }
 }
 
@@ -251,11 +695,22 @@ impl Deref for SignedOrder {
     }
 }
 
+// ethers encodes a struct as the sequence of its fields, without the offset of a single tuple
 fn into_alloy_resolved_order(ethers: EthersResolvedOrder) -> ResolvedOrder {
-    ResolvedOrder::decode_single(&ethers.encode(), true)
+    ResolvedOrder::decode(&ethers.encode(), true)
         .expect("for ethers abi encoding to parse into an alloys resolved order")
 }
 
+impl From<&Order> for DecodedOrder {
+    fn from(order: &Order) -> Self {
+        match order.inner.clone() {
+            OrderInner::Dutch(o) => DecodedOrder::Dutch(o.into()),
+            OrderInner::Limit(o) => DecodedOrder::Limit(o.into()),
+            OrderInner::ExclusiveDutch(o) => DecodedOrder::ExclusiveDutch(o.into()),
+        }
+    }
+}
+
 impl From<DutchOrder> for OrderInner {
     fn from(order: DutchOrder) -> Self {
         OrderInner::Dutch(order)
@@ -328,6 +783,7 @@ impl<M: Middleware> std::fmt::Display for ValidationError<M> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ValidationError::ContractError(e) => write!(f, "ContractError: {}", e),
+            ValidationError::MulticallError(e) => write!(f, "MulticallError: {}", e),
             ValidationError::SigParseError(e) => write!(f, "SigParseError: {}", e),
         }
     }
@@ -339,6 +795,12 @@ impl<M: Middleware> From<ContractError<M>> for ValidationError<M> {
     }
 }
 
+impl<M: Middleware> From<MulticallError<M>> for ValidationError<M> {
+    fn from(e: MulticallError<M>) -> Self {
+        ValidationError::MulticallError(e)
+    }
+}
+
 impl<M: Middleware> From<ParseBytesError> for ValidationError<M> {
     fn from(e: ParseBytesError) -> Self {
         ValidationError::SigParseError(e)
@@ -0,0 +1,461 @@
+use crate::contracts::{
+    common::{InputToken, OrderInfo, OutputToken, ResolvedOrder},
+    internal::{
+        dutch::{DutchInput, DutchOrder, DutchOutput},
+        exclusive_dutch::ExclusiveDutchOrder,
+        limit::LimitOrder,
+    },
+};
+use alloy_primitives::{Address, U256};
+
+/// see `ExclusivityOverrideLib`
+const BPS: u64 = 10_000;
+
+/// the reasons a reactor would revert while resolving an order
+///
+/// see https://github.com/Uniswap/UniswapX/blob/main/src/lib/DutchDecayLib.sol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolutionError {
+    /// the decay end time is before the decay start time
+    EndTimeBeforeStartTime,
+    /// the order deadline is before the decay end time
+    DeadlineBeforeEndTime,
+    /// both the input and at least one output decay
+    InputAndOutputDecay,
+    /// the input decays downwards or an output decays upwards
+    IncorrectAmounts,
+    /// a `mulDiv` overflowed, solmate would revert here
+    MulDivOverflow,
+    /// the filler is not the exclusive filler and the order is strictly exclusive
+    NoExclusiveOverride,
+}
+
+/// a concrete order type that can be resolved into a [ResolvedOrder] at a given block timestamp
+///
+/// implementors should match the `resolve` function of their reactor exactly
+pub trait Resolve {
+    fn resolve_at(&self, timestamp: U256) -> Result<ResolvedOrder, ResolutionError>;
+
+    /// resolve the order as if `filler` were the `tx.origin` of the fill
+    ///
+    /// by default this is the same as [Resolve::resolve_at], orders with exclusivity should override this
+    fn resolve_for(
+        &self,
+        _filler: Address,
+        timestamp: U256,
+    ) -> Result<ResolvedOrder, ResolutionError> {
+        self.resolve_at(timestamp)
+    }
+}
+
+impl Resolve for DutchOrder {
+    fn resolve_at(&self, timestamp: U256) -> Result<ResolvedOrder, ResolutionError> {
+        validate_dutch(
+            &self.info,
+            self.decayStartTime,
+            self.decayEndTime,
+            &self.input,
+            &self.outputs,
+        )?;
+
+        resolve_dutch(
+            &self.info,
+            self.decayStartTime,
+            self.decayEndTime,
+            &self.input,
+            &self.outputs,
+            timestamp,
+        )
+    }
+}
+
+impl Resolve for ExclusiveDutchOrder {
+    fn resolve_at(&self, timestamp: U256) -> Result<ResolvedOrder, ResolutionError> {
+        validate_dutch(
+            &self.info,
+            self.decayStartTime,
+            self.decayEndTime,
+            &self.input,
+            &self.outputs,
+        )?;
+
+        resolve_dutch(
+            &self.info,
+            self.decayStartTime,
+            self.decayEndTime,
+            &self.input,
+            &self.outputs,
+            timestamp,
+        )
+    }
+
+    /// the outputs are scaled up by `exclusivityOverrideBps` if the filler doesnt have filling rights
+    fn resolve_for(
+        &self,
+        filler: Address,
+        timestamp: U256,
+    ) -> Result<ResolvedOrder, ResolutionError> {
+        let mut resolved = self.resolve_at(timestamp)?;
+
+        handle_override(
+            &mut resolved,
+            self.exclusiveFiller,
+            self.decayStartTime,
+            self.exclusivityOverrideBps,
+            filler,
+            timestamp,
+        )?;
+
+        Ok(resolved)
+    }
+}
+
+impl ExclusiveDutchOrder {
+    /// true if `filler` can fill the order at `timestamp` without any override
+    pub fn has_filling_rights(&self, filler: Address, timestamp: U256) -> bool {
+        has_filling_rights(self.exclusiveFiller, self.decayStartTime, filler, timestamp)
+    }
+}
+
+impl Resolve for LimitOrder {
+    /// limit orders dont decay, so this is just a conversion
+    fn resolve_at(&self, _timestamp: U256) -> Result<ResolvedOrder, ResolutionError> {
+        Ok(ResolvedOrder {
+            info: self.info.clone(),
+            input: self.input.clone(),
+            outputs: self.outputs.clone(),
+            sig: Vec::new(),
+            hash: [0u8; 32],
+        })
+    }
+}
+
+/// mirrors `_validateOrder` in the dutch reactors
+fn validate_dutch(
+    info: &OrderInfo,
+    decay_start_time: U256,
+    decay_end_time: U256,
+    input: &DutchInput,
+    outputs: &[DutchOutput],
+) -> Result<(), ResolutionError> {
+    if info.deadline < decay_end_time {
+        return Err(ResolutionError::DeadlineBeforeEndTime);
+    }
+
+    if decay_end_time < decay_start_time {
+        return Err(ResolutionError::EndTimeBeforeStartTime);
+    }
+
+    if input.startAmount != input.endAmount && outputs.iter().any(|o| o.startAmount != o.endAmount)
+    {
+        return Err(ResolutionError::InputAndOutputDecay);
+    }
+
+    Ok(())
+}
+
+fn resolve_dutch(
+    info: &OrderInfo,
+    decay_start_time: U256,
+    decay_end_time: U256,
+    input: &DutchInput,
+    outputs: &[DutchOutput],
+    timestamp: U256,
+) -> Result<ResolvedOrder, ResolutionError> {
+    let input = decay_input(input, decay_start_time, decay_end_time, timestamp)?;
+
+    let outputs = outputs
+        .iter()
+        .map(|output| decay_output(output, decay_start_time, decay_end_time, timestamp))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ResolvedOrder {
+        info: info.clone(),
+        input,
+        outputs,
+        sig: Vec::new(),
+        hash: [0u8; 32],
+    })
+}
+
+/// the input can only increase over time, `maxAmount` is always the end amount
+fn decay_input(
+    input: &DutchInput,
+    decay_start_time: U256,
+    decay_end_time: U256,
+    timestamp: U256,
+) -> Result<InputToken, ResolutionError> {
+    if input.startAmount > input.endAmount {
+        return Err(ResolutionError::IncorrectAmounts);
+    }
+
+    Ok(InputToken {
+        token: input.token,
+        amount: decay(
+            input.startAmount,
+            input.endAmount,
+            decay_start_time,
+            decay_end_time,
+            timestamp,
+        )?,
+        maxAmount: input.endAmount,
+    })
+}
+
+/// the outputs can only decrease over time
+fn decay_output(
+    output: &DutchOutput,
+    decay_start_time: U256,
+    decay_end_time: U256,
+    timestamp: U256,
+) -> Result<OutputToken, ResolutionError> {
+    if output.startAmount < output.endAmount {
+        return Err(ResolutionError::IncorrectAmounts);
+    }
+
+    Ok(OutputToken {
+        token: output.token,
+        amount: decay(
+            output.startAmount,
+            output.endAmount,
+            decay_start_time,
+            decay_end_time,
+            timestamp,
+        )?,
+        recipient: output.recipient,
+    })
+}
+
+/// linear decay from `start_amount` to `end_amount`, a port of `DutchDecayLib.decay`
+///
+/// the decayed delta is rounded down for decreasing amounts and up for increasing amounts, same as solidity
+pub fn decay(
+    start_amount: U256,
+    end_amount: U256,
+    decay_start_time: U256,
+    decay_end_time: U256,
+    timestamp: U256,
+) -> Result<U256, ResolutionError> {
+    if decay_end_time < decay_start_time {
+        return Err(ResolutionError::EndTimeBeforeStartTime);
+    }
+
+    if start_amount == end_amount {
+        return Ok(start_amount);
+    }
+
+    if decay_end_time <= timestamp {
+        return Ok(end_amount);
+    }
+
+    if decay_start_time >= timestamp {
+        return Ok(start_amount);
+    }
+
+    let elapsed = timestamp - decay_start_time;
+    let duration = decay_end_time - decay_start_time;
+
+    if end_amount < start_amount {
+        Ok(start_amount - mul_div_down(start_amount - end_amount, elapsed, duration)?)
+    } else {
+        Ok(start_amount + mul_div_up(end_amount - start_amount, elapsed, duration)?)
+    }
+}
+
+/// a port of `ExclusivityOverrideLib.hasFillingRights`
+///
+/// anyone can fill if there is no exclusive filler or the exclusivity period has ended
+pub fn has_filling_rights(
+    exclusive: Address,
+    exclusivity_end_time: U256,
+    filler: Address,
+    timestamp: U256,
+) -> bool {
+    exclusive == Address::ZERO || timestamp > exclusivity_end_time || exclusive == filler
+}
+
+/// a port of `ExclusivityOverrideLib.handleOverride`
+///
+/// scales the outputs of `order` up by `exclusivity_override_bps` if `filler` has no filling rights, rounding up,
+/// an override of zero means the order is strictly exclusive
+pub fn handle_override(
+    order: &mut ResolvedOrder,
+    exclusive: Address,
+    exclusivity_end_time: U256,
+    exclusivity_override_bps: U256,
+    filler: Address,
+    timestamp: U256,
+) -> Result<(), ResolutionError> {
+    if has_filling_rights(exclusive, exclusivity_end_time, filler, timestamp) {
+        return Ok(());
+    }
+
+    if exclusivity_override_bps == U256::ZERO {
+        return Err(ResolutionError::NoExclusiveOverride);
+    }
+
+    let bps = U256::from(BPS);
+    let scale = bps
+        .checked_add(exclusivity_override_bps)
+        .ok_or(ResolutionError::MulDivOverflow)?;
+
+    for output in order.outputs.iter_mut() {
+        output.amount = mul_div_up(output.amount, scale, bps)?;
+    }
+
+    Ok(())
+}
+
+/// a port of solmate's `FixedPointMathLib.mulDivDown`
+pub fn mul_div_down(x: U256, y: U256, denominator: U256) -> Result<U256, ResolutionError> {
+    if denominator == U256::ZERO {
+        return Err(ResolutionError::MulDivOverflow);
+    }
+
+    Ok(x.checked_mul(y).ok_or(ResolutionError::MulDivOverflow)? / denominator)
+}
+
+/// a port of solmate's `FixedPointMathLib.mulDivUp`
+pub fn mul_div_up(x: U256, y: U256, denominator: U256) -> Result<U256, ResolutionError> {
+    if denominator == U256::ZERO {
+        return Err(ResolutionError::MulDivOverflow);
+    }
+
+    let product = x.checked_mul(y).ok_or(ResolutionError::MulDivOverflow)?;
+
+    if product % denominator == U256::ZERO {
+        Ok(product / denominator)
+    } else {
+        Ok(product / denominator + U256::from(1))
+    }
+}
+
+impl std::error::Error for ResolutionError {}
+
+impl std::fmt::Display for ResolutionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResolutionError::EndTimeBeforeStartTime => write!(f, "EndTimeBeforeStartTime"),
+            ResolutionError::DeadlineBeforeEndTime => write!(f, "DeadlineBeforeEndTime"),
+            ResolutionError::InputAndOutputDecay => write!(f, "InputAndOutputDecay"),
+            ResolutionError::IncorrectAmounts => write!(f, "IncorrectAmounts"),
+            ResolutionError::MulDivOverflow => write!(f, "MulDivOverflow"),
+            ResolutionError::NoExclusiveOverride => write!(f, "NoExclusiveOverride"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u(n: u64) -> U256 {
+        U256::from(n)
+    }
+
+    #[test]
+    fn decay_before_start_and_after_end() {
+        assert_eq!(decay(u(2000), u(1000), u(100), u(200), u(50)), Ok(u(2000)));
+        assert_eq!(decay(u(2000), u(1000), u(100), u(200), u(100)), Ok(u(2000)));
+        assert_eq!(decay(u(2000), u(1000), u(100), u(200), u(200)), Ok(u(1000)));
+        assert_eq!(decay(u(2000), u(1000), u(100), u(200), u(300)), Ok(u(1000)));
+    }
+
+    #[test]
+    fn decay_downwards_rounds_the_delta_down() {
+        assert_eq!(decay(u(2000), u(1000), u(100), u(200), u(150)), Ok(u(1500)));
+        // 1000 * 1 / 3 = 333.33, the output keeps the larger amount
+        assert_eq!(decay(u(2000), u(1000), u(0), u(3), u(1)), Ok(u(1667)));
+    }
+
+    #[test]
+    fn decay_upwards_rounds_the_delta_up() {
+        assert_eq!(decay(u(1000), u(2000), u(100), u(200), u(150)), Ok(u(1500)));
+        // 1000 * 1 / 3 = 333.33, the input takes the larger amount
+        assert_eq!(decay(u(1000), u(2000), u(0), u(3), u(1)), Ok(u(1334)));
+    }
+
+    #[test]
+    fn decay_without_change_or_duration() {
+        assert_eq!(decay(u(1000), u(1000), u(0), u(3), u(1)), Ok(u(1000)));
+        // the end time is checked first, so a zero length decay is already over at its start
+        assert_eq!(decay(u(2000), u(1000), u(100), u(100), u(99)), Ok(u(2000)));
+        assert_eq!(decay(u(2000), u(1000), u(100), u(100), u(100)), Ok(u(1000)));
+        assert_eq!(
+            decay(u(2000), u(1000), u(200), u(100), u(150)),
+            Err(ResolutionError::EndTimeBeforeStartTime)
+        );
+    }
+
+    #[test]
+    fn mul_div_rounding() {
+        assert_eq!(mul_div_down(u(10), u(1), u(3)), Ok(u(3)));
+        assert_eq!(mul_div_up(u(10), u(1), u(3)), Ok(u(4)));
+        assert_eq!(mul_div_up(u(9), u(1), u(3)), Ok(u(3)));
+        assert_eq!(
+            mul_div_down(U256::MAX, u(2), u(1)),
+            Err(ResolutionError::MulDivOverflow)
+        );
+        assert_eq!(
+            mul_div_up(u(1), u(1), u(0)),
+            Err(ResolutionError::MulDivOverflow)
+        );
+    }
+
+    fn resolved(amount: U256) -> ResolvedOrder {
+        ResolvedOrder {
+            info: OrderInfo {
+                reactor: Address::ZERO,
+                swapper: Address::ZERO,
+                nonce: U256::ZERO,
+                deadline: U256::ZERO,
+                additionalValidationContract: Address::ZERO,
+                additionalValidationData: Vec::new(),
+            },
+            input: InputToken {
+                token: Address::ZERO,
+                amount: U256::ZERO,
+                maxAmount: U256::ZERO,
+            },
+            outputs: vec![OutputToken {
+                token: Address::ZERO,
+                amount,
+                recipient: Address::ZERO,
+            }],
+            sig: Vec::new(),
+            hash: [0u8; 32],
+        }
+    }
+
+    #[test]
+    fn override_rounds_the_outputs_up() {
+        let exclusive = Address::repeat_byte(1);
+        let filler = Address::repeat_byte(2);
+
+        // 1001 * 10100 / 10000 = 1011.01, the reactor demands 1012
+        let mut order = resolved(u(1001));
+        handle_override(&mut order, exclusive, u(100), u(100), filler, u(50)).unwrap();
+        assert_eq!(order.outputs[0].amount, u(1012));
+
+        let mut order = resolved(u(1000));
+        handle_override(&mut order, exclusive, u(100), u(100), filler, u(50)).unwrap();
+        assert_eq!(order.outputs[0].amount, u(1010));
+    }
+
+    #[test]
+    fn override_only_applies_without_filling_rights() {
+        let exclusive = Address::repeat_byte(1);
+        let filler = Address::repeat_byte(2);
+
+        let mut order = resolved(u(1001));
+        handle_override(&mut order, exclusive, u(100), u(100), exclusive, u(50)).unwrap();
+        handle_override(&mut order, exclusive, u(100), u(100), filler, u(101)).unwrap();
+        handle_override(&mut order, Address::ZERO, u(100), u(100), filler, u(50)).unwrap();
+        assert_eq!(order.outputs[0].amount, u(1001));
+
+        assert_eq!(
+            handle_override(&mut order, exclusive, u(100), U256::ZERO, filler, u(50)),
+            Err(ResolutionError::NoExclusiveOverride)
+        );
+    }
+}
@@ -0,0 +1,456 @@
+use alloy_primitives::{Address, U256};
+use ethers::{
+    abi::AbiDecode,
+    contract::EthError,
+    types::{Address as EthersAddress, U256 as EthersU256},
+};
+use uniswapx_ethers_bindings::{
+    exclusive_dutch_order_reactor::ExclusiveDutchOrderReactorErrors,
+    exclusive_filler_validation::NotExclusiveFiller,
+    i_allowance_transfer::IAllowanceTransferErrors, i_signature_transfer::ISignatureTransferErrors,
+    order_quoter::OrdersLengthIncorrect,
+};
+
+/// the revert string solmate's `SafeTransferLib` uses when a `transferFrom` fails,
+/// permit2 will revert with this if the swapper has an insufficient balance or allowance
+pub const TRANSFER_FROM_FAILED: &str = "TRANSFER_FROM_FAILED";
+
+/// a decoded revert from a reactor, permit2 or the order quoter
+///
+/// see [RevertReason::decode]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevertReason {
+    // reactor errors
+    DeadlinePassed,
+    InvalidReactor,
+    DeadlineBeforeEndTime,
+    EndTimeBeforeStartTime,
+    OrderEndTimeBeforeStartTime,
+    InputAndOutputDecay,
+    IncorrectAmounts,
+    NoExclusiveOverride,
+    NotExclusiveFiller {
+        filler: Address,
+    },
+    InsufficientEth,
+    InsufficientOutput {
+        actual_balance: U256,
+        expected_balance: U256,
+    },
+    NativeTransferFailed,
+    DuplicateFeeOutput {
+        duplicate_token: Address,
+    },
+    FeeTooLarge {
+        token: Address,
+        amount: U256,
+        recipient: Address,
+    },
+    InvalidFeeToken {
+        fee_token: Address,
+    },
+    /// the additional validation contract rejected the order
+    ValidationFailed,
+
+    // order quoter errors
+    OrdersLengthIncorrect,
+
+    // permit2 errors
+    InvalidNonce,
+    SignatureExpired {
+        signature_deadline: U256,
+    },
+    InvalidSignature,
+    InvalidSigner,
+    InvalidSignatureLength,
+    InvalidContractSignature,
+    InvalidAmount {
+        max_amount: U256,
+    },
+    LengthMismatch,
+    AllowanceExpired {
+        deadline: U256,
+    },
+    InsufficientAllowance {
+        amount: U256,
+    },
+    ExcessiveInvalidation,
+
+    // erc20 errors
+    /// the input token transfer from the swapper failed
+    TransferFromFailed,
+
+    /// some other `Error(string)`
+    RevertString(String),
+    /// a solidity `Panic(uint256)`, e.g. `0x11` for an overflow
+    Panic {
+        code: U256,
+    },
+    /// a revert that doesnt match any known error
+    Unknown(Vec<u8>),
+}
+
+/// the builtin solidity `Panic(uint256)`
+#[derive(Clone, Debug, Default, Eq, PartialEq, EthError)]
+#[etherror(name = "Panic", abi = "Panic(uint256)")]
+struct Panic {
+    code: EthersU256,
+}
+
+/// permit2 `SignatureTransfer` errors that arent in the bindings
+mod permit2 {
+    use ethers::contract::EthError;
+
+    #[derive(Clone, Debug, Default, Eq, PartialEq, EthError)]
+    #[etherror(name = "InvalidNonce", abi = "InvalidNonce()")]
+    pub struct InvalidNonce;
+
+    #[derive(Clone, Debug, Default, Eq, PartialEq, EthError)]
+    #[etherror(name = "SignatureExpired", abi = "SignatureExpired(uint256)")]
+    pub struct SignatureExpired {
+        pub signature_deadline: ethers::types::U256,
+    }
+
+    #[derive(Clone, Debug, Default, Eq, PartialEq, EthError)]
+    #[etherror(name = "InvalidSignature", abi = "InvalidSignature()")]
+    pub struct InvalidSignature;
+
+    #[derive(Clone, Debug, Default, Eq, PartialEq, EthError)]
+    #[etherror(name = "InvalidSigner", abi = "InvalidSigner()")]
+    pub struct InvalidSigner;
+
+    #[derive(Clone, Debug, Default, Eq, PartialEq, EthError)]
+    #[etherror(name = "InvalidSignatureLength", abi = "InvalidSignatureLength()")]
+    pub struct InvalidSignatureLength;
+
+    #[derive(Clone, Debug, Default, Eq, PartialEq, EthError)]
+    #[etherror(name = "InvalidContractSignature", abi = "InvalidContractSignature()")]
+    pub struct InvalidContractSignature;
+
+    /// `IValidationCallback` implementations revert with this
+    #[derive(Clone, Debug, Default, Eq, PartialEq, EthError)]
+    #[etherror(name = "ValidationFailed", abi = "ValidationFailed()")]
+    pub struct ValidationFailed;
+}
+
+impl RevertReason {
+    /// decode the revert data of a call to a reactor, permit2 or the order quoter
+    pub fn decode(data: &[u8]) -> Self {
+        if let Some(s) = String::decode_with_selector(data) {
+            return s.into();
+        }
+
+        if let Some(err) = decode::<ExclusiveDutchOrderReactorErrors>(data) {
+            return err.into();
+        }
+
+        if let Some(err) = decode::<ISignatureTransferErrors>(data) {
+            return err.into();
+        }
+
+        if let Some(err) = decode::<IAllowanceTransferErrors>(data) {
+            return err.into();
+        }
+
+        if let Some(err) = decode::<NotExclusiveFiller>(data) {
+            return RevertReason::NotExclusiveFiller {
+                filler: into_alloy_address(err.filler),
+            };
+        }
+
+        if decode::<OrdersLengthIncorrect>(data).is_some() {
+            return RevertReason::OrdersLengthIncorrect;
+        }
+
+        if decode::<permit2::InvalidNonce>(data).is_some() {
+            return RevertReason::InvalidNonce;
+        }
+
+        if let Some(err) = decode::<permit2::SignatureExpired>(data) {
+            return RevertReason::SignatureExpired {
+                signature_deadline: into_alloy_u256(err.signature_deadline),
+            };
+        }
+
+        if decode::<permit2::InvalidSignature>(data).is_some() {
+            return RevertReason::InvalidSignature;
+        }
+
+        if decode::<permit2::InvalidSigner>(data).is_some() {
+            return RevertReason::InvalidSigner;
+        }
+
+        if decode::<permit2::InvalidSignatureLength>(data).is_some() {
+            return RevertReason::InvalidSignatureLength;
+        }
+
+        if decode::<permit2::InvalidContractSignature>(data).is_some() {
+            return RevertReason::InvalidContractSignature;
+        }
+
+        if decode::<permit2::ValidationFailed>(data).is_some() {
+            return RevertReason::ValidationFailed;
+        }
+
+        if let Some(err) = decode::<Panic>(data) {
+            return RevertReason::Panic {
+                code: into_alloy_u256(err.code),
+            };
+        }
+
+        RevertReason::Unknown(data.to_vec())
+    }
+
+    /// the 4 byte selector of an unknown revert, hex encoded with a `0x` prefix
+    pub fn selector(&self) -> Option<String> {
+        match self {
+            RevertReason::Unknown(data) if data.len() >= 4 => {
+                Some(format!("0x{}", hex::encode(&data[..4])))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<ExclusiveDutchOrderReactorErrors> for RevertReason {
+    fn from(err: ExclusiveDutchOrderReactorErrors) -> Self {
+        match err {
+            ExclusiveDutchOrderReactorErrors::DeadlineBeforeEndTime(_) => {
+                RevertReason::DeadlineBeforeEndTime
+            }
+            ExclusiveDutchOrderReactorErrors::DeadlinePassed(_) => RevertReason::DeadlinePassed,
+            ExclusiveDutchOrderReactorErrors::DuplicateFeeOutput(e) => {
+                RevertReason::DuplicateFeeOutput {
+                    duplicate_token: into_alloy_address(e.duplicate_token),
+                }
+            }
+            ExclusiveDutchOrderReactorErrors::EndTimeBeforeStartTime(_) => {
+                RevertReason::EndTimeBeforeStartTime
+            }
+            ExclusiveDutchOrderReactorErrors::FeeTooLarge(e) => RevertReason::FeeTooLarge {
+                token: into_alloy_address(e.token),
+                amount: into_alloy_u256(e.amount),
+                recipient: into_alloy_address(e.recipient),
+            },
+            ExclusiveDutchOrderReactorErrors::IncorrectAmounts(_) => RevertReason::IncorrectAmounts,
+            ExclusiveDutchOrderReactorErrors::InputAndOutputDecay(_) => {
+                RevertReason::InputAndOutputDecay
+            }
+            ExclusiveDutchOrderReactorErrors::InsufficientEth(_) => RevertReason::InsufficientEth,
+            ExclusiveDutchOrderReactorErrors::InsufficientOutput(e) => {
+                RevertReason::InsufficientOutput {
+                    actual_balance: into_alloy_u256(e.actual_balance),
+                    expected_balance: into_alloy_u256(e.expected_balance),
+                }
+            }
+            ExclusiveDutchOrderReactorErrors::InvalidFeeToken(e) => RevertReason::InvalidFeeToken {
+                fee_token: into_alloy_address(e.fee_token),
+            },
+            ExclusiveDutchOrderReactorErrors::InvalidReactor(_) => RevertReason::InvalidReactor,
+            ExclusiveDutchOrderReactorErrors::NativeTransferFailed(_) => {
+                RevertReason::NativeTransferFailed
+            }
+            ExclusiveDutchOrderReactorErrors::NoExclusiveOverride(_) => {
+                RevertReason::NoExclusiveOverride
+            }
+            ExclusiveDutchOrderReactorErrors::OrderEndTimeBeforeStartTime(_) => {
+                RevertReason::OrderEndTimeBeforeStartTime
+            }
+            ExclusiveDutchOrderReactorErrors::RevertString(s) => RevertReason::from(s),
+        }
+    }
+}
+
+impl From<ISignatureTransferErrors> for RevertReason {
+    fn from(err: ISignatureTransferErrors) -> Self {
+        match err {
+            ISignatureTransferErrors::InvalidAmount(e) => RevertReason::InvalidAmount {
+                max_amount: into_alloy_u256(e.max_amount),
+            },
+            ISignatureTransferErrors::LengthMismatch(_) => RevertReason::LengthMismatch,
+            ISignatureTransferErrors::RevertString(s) => RevertReason::from(s),
+        }
+    }
+}
+
+impl From<IAllowanceTransferErrors> for RevertReason {
+    fn from(err: IAllowanceTransferErrors) -> Self {
+        match err {
+            IAllowanceTransferErrors::AllowanceExpired(e) => RevertReason::AllowanceExpired {
+                deadline: into_alloy_u256(e.deadline),
+            },
+            IAllowanceTransferErrors::ExcessiveInvalidation(_) => {
+                RevertReason::ExcessiveInvalidation
+            }
+            IAllowanceTransferErrors::InsufficientAllowance(e) => {
+                RevertReason::InsufficientAllowance {
+                    amount: into_alloy_u256(e.amount),
+                }
+            }
+            IAllowanceTransferErrors::RevertString(s) => RevertReason::from(s),
+        }
+    }
+}
+
+impl From<String> for RevertReason {
+    fn from(s: String) -> Self {
+        if s == TRANSFER_FROM_FAILED {
+            RevertReason::TransferFromFailed
+        } else {
+            RevertReason::RevertString(s)
+        }
+    }
+}
+
+impl std::fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RevertReason::RevertString(s) => write!(f, "Error({})", s),
+            RevertReason::Unknown(data) => write!(f, "Unknown(0x{})", hex::encode(data)),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// the abigen [AbiDecode] impls for errors expect the selector to be included
+fn decode<T: AbiDecode>(data: &[u8]) -> Option<T> {
+    T::decode(data).ok()
+}
+
+pub(crate) fn into_alloy_address(address: EthersAddress) -> Address {
+    Address::from(address.0)
+}
+
+pub(crate) fn into_alloy_u256(value: EthersU256) -> U256 {
+    U256::from_limbs(value.0)
+}
+
+pub(crate) fn into_ethers_address(address: Address) -> EthersAddress {
+    EthersAddress::from(address.0 .0)
+}
+
+pub(crate) fn into_ethers_u256(value: U256) -> EthersU256 {
+    EthersU256(value.into_limbs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::keccak256;
+    use ethers::abi::{encode, Token};
+
+    /// the revert data of a custom error, the selector is taken from the signature rather than the bindings
+    fn revert(signature: &str, args: &[Token]) -> Vec<u8> {
+        [&keccak256(signature)[..4], &encode(args)].concat()
+    }
+
+    fn uint(n: u64) -> Token {
+        Token::Uint(n.into())
+    }
+
+    #[test]
+    fn decodes_reactor_errors() {
+        let filler = Address::repeat_byte(9);
+
+        assert_eq!(
+            RevertReason::decode(&revert("DeadlinePassed()", &[])),
+            RevertReason::DeadlinePassed
+        );
+        assert_eq!(
+            RevertReason::decode(&revert("NoExclusiveOverride()", &[])),
+            RevertReason::NoExclusiveOverride
+        );
+        assert_eq!(
+            RevertReason::decode(&revert(
+                "InsufficientOutput(uint256,uint256)",
+                &[uint(1), uint(2)]
+            )),
+            RevertReason::InsufficientOutput {
+                actual_balance: U256::from(1),
+                expected_balance: U256::from(2),
+            }
+        );
+        assert_eq!(
+            RevertReason::decode(&revert(
+                "NotExclusiveFiller(address)",
+                &[Token::Address(into_ethers_address(filler))]
+            )),
+            RevertReason::NotExclusiveFiller { filler }
+        );
+        assert_eq!(
+            RevertReason::decode(&revert("OrdersLengthIncorrect()", &[])),
+            RevertReason::OrdersLengthIncorrect
+        );
+    }
+
+    #[test]
+    fn decodes_permit2_errors() {
+        assert_eq!(
+            RevertReason::decode(&revert("InvalidNonce()", &[])),
+            RevertReason::InvalidNonce
+        );
+        assert_eq!(
+            RevertReason::decode(&revert("SignatureExpired(uint256)", &[uint(5)])),
+            RevertReason::SignatureExpired {
+                signature_deadline: U256::from(5)
+            }
+        );
+        assert_eq!(
+            RevertReason::decode(&revert("InvalidSigner()", &[])),
+            RevertReason::InvalidSigner
+        );
+        assert_eq!(
+            RevertReason::decode(&revert("InvalidContractSignature()", &[])),
+            RevertReason::InvalidContractSignature
+        );
+        assert_eq!(
+            RevertReason::decode(&revert("InvalidAmount(uint256)", &[uint(100)])),
+            RevertReason::InvalidAmount {
+                max_amount: U256::from(100)
+            }
+        );
+        assert_eq!(
+            RevertReason::decode(&revert("InsufficientAllowance(uint256)", &[uint(7)])),
+            RevertReason::InsufficientAllowance {
+                amount: U256::from(7)
+            }
+        );
+    }
+
+    #[test]
+    fn decodes_revert_strings_and_panics() {
+        let error = |s: &str| revert("Error(string)", &[Token::String(s.to_string())]);
+
+        assert_eq!(&error("")[..4], [0x08, 0xc3, 0x79, 0xa0]);
+        assert_eq!(
+            RevertReason::decode(&error(TRANSFER_FROM_FAILED)),
+            RevertReason::TransferFromFailed
+        );
+        assert_eq!(
+            RevertReason::decode(&error("nope")),
+            RevertReason::RevertString("nope".to_string())
+        );
+
+        let panic = revert("Panic(uint256)", &[uint(0x11)]);
+        assert_eq!(&panic[..4], [0x4e, 0x48, 0x7b, 0x71]);
+        assert_eq!(
+            RevertReason::decode(&panic),
+            RevertReason::Panic {
+                code: U256::from(0x11)
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_reverts_keep_their_data() {
+        assert_eq!(RevertReason::decode(&[]), RevertReason::Unknown(vec![]));
+        assert_eq!(RevertReason::decode(&[]).selector(), None);
+
+        let data = [0xde, 0xad, 0xbe, 0xef, 0x01];
+        let reason = RevertReason::decode(&data);
+
+        assert_eq!(reason, RevertReason::Unknown(data.to_vec()));
+        assert_eq!(reason.selector().as_deref(), Some("0xdeadbeef"));
+        assert_eq!(reason.to_string(), "Unknown(0xdeadbeef01)");
+    }
+}
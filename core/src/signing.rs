@@ -0,0 +1,406 @@
+use crate::contracts::internal::{
+    dutch::DutchOrder, exclusive_dutch::ExclusiveDutchOrder, limit::LimitOrder,
+};
+use alloy_primitives::{keccak256, Address, B256, U256};
+use alloy_sol_types::{Eip712Domain, SolStruct};
+use ethers::{
+    abi::Token,
+    types::{Signature, SignatureError, U256 as EthersU256},
+};
+use std::borrow::Cow;
+
+/// the canonical permit2 deployment, its the same address on every chain
+pub const PERMIT2_ADDRESS: &str = "0x000000000022D473030F116dDEE9F6B43aC78BA3";
+
+/// the selector of `isValidSignature(bytes32,bytes)`, which is also the value a valid EIP-1271 signature returns
+pub const EIP1271_MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+
+/// see `OrderInfoLib.ORDER_INFO_TYPE`
+pub const ORDER_INFO_TYPE: &str = "OrderInfo(address reactor,address swapper,uint256 nonce,uint256 deadline,address additionalValidationContract,bytes additionalValidationData)";
+
+/// see `DutchOrderLib.DUTCH_OUTPUT_TYPE`
+pub const DUTCH_OUTPUT_TYPE: &str =
+    "DutchOutput(address token,uint256 startAmount,uint256 endAmount,address recipient)";
+
+/// see `LimitOrderLib.OUTPUT_TOKEN_TYPE`
+pub const OUTPUT_TOKEN_TYPE: &str = "OutputToken(address token,uint256 amount,address recipient)";
+
+/// see `Permit2Lib.TOKEN_PERMISSIONS_TYPE`
+pub const TOKEN_PERMISSIONS_TYPE: &str = "TokenPermissions(address token,uint256 amount)";
+
+/// see `PermitHash._PERMIT_TRANSFER_FROM_WITNESS_TYPEHASH_STUB`
+pub const PERMIT_WITNESS_TRANSFER_FROM_TYPE_STUB: &str =
+    "PermitWitnessTransferFrom(TokenPermissions permitted,address spender,uint256 nonce,uint256 deadline,";
+
+/// see `DutchOrderLib.DUTCH_ORDER_TYPE`
+pub const DUTCH_ORDER_TYPE: &str = "DutchOrder(OrderInfo info,uint256 decayStartTime,uint256 decayEndTime,address inputToken,uint256 inputStartAmount,uint256 inputEndAmount,DutchOutput[] outputs)";
+
+/// see `ExclusiveDutchOrderLib.EXCLUSIVE_DUTCH_ORDER_TYPE`
+pub const EXCLUSIVE_DUTCH_ORDER_TYPE: &str = "ExclusiveDutchOrder(OrderInfo info,uint256 decayStartTime,uint256 decayEndTime,address exclusiveFiller,uint256 exclusivityOverrideBps,address inputToken,uint256 inputStartAmount,uint256 inputEndAmount,DutchOutput[] outputs)";
+
+/// see `LimitOrderLib.LIMIT_ORDER_TYPE`
+pub const LIMIT_ORDER_TYPE: &str =
+    "LimitOrder(OrderInfo info,address inputToken,uint256 inputAmount,OutputToken[] outputs)";
+
+/// an order type that is signed as the witness of a permit2 `PermitWitnessTransferFrom`
+///
+/// the reactors flatten the input into the order struct, so the alloy eip712 hash of the abi type
+/// is not the same as the order hash the reactor computes, implementors should match the reactor libs
+pub trait Permit2Witness {
+    /// the name of the witness struct, e.g. `DutchOrder`
+    const WITNESS_NAME: &'static str;
+
+    /// the full eip712 type of the order, including the referenced types
+    fn order_type() -> String;
+
+    /// the order hash as computed by the reactor, this is also the permit2 witness
+    fn order_hash(&self) -> B256;
+
+    /// the token and max amount the swapper is permitting the reactor to spend
+    fn permitted(&self) -> (Address, U256);
+
+    /// the witness type string passed to permit2
+    fn witness_type_string() -> String {
+        format!(
+            "{} witness){}{}",
+            Self::WITNESS_NAME,
+            Self::order_type(),
+            TOKEN_PERMISSIONS_TYPE
+        )
+    }
+}
+
+impl Permit2Witness for DutchOrder {
+    const WITNESS_NAME: &'static str = "DutchOrder";
+
+    fn order_type() -> String {
+        [DUTCH_ORDER_TYPE, DUTCH_OUTPUT_TYPE, ORDER_INFO_TYPE].concat()
+    }
+
+    fn order_hash(&self) -> B256 {
+        hash_words(&[
+            keccak256(Self::order_type()).0,
+            self.info.eip712_hash_struct().0,
+            self.decayStartTime.to_be_bytes(),
+            self.decayEndTime.to_be_bytes(),
+            address_word(self.input.token),
+            self.input.startAmount.to_be_bytes(),
+            self.input.endAmount.to_be_bytes(),
+            hash_structs(&self.outputs).0,
+        ])
+    }
+
+    fn permitted(&self) -> (Address, U256) {
+        (self.input.token, self.input.endAmount)
+    }
+}
+
+impl Permit2Witness for ExclusiveDutchOrder {
+    const WITNESS_NAME: &'static str = "ExclusiveDutchOrder";
+
+    fn order_type() -> String {
+        [
+            EXCLUSIVE_DUTCH_ORDER_TYPE,
+            DUTCH_OUTPUT_TYPE,
+            ORDER_INFO_TYPE,
+        ]
+        .concat()
+    }
+
+    fn order_hash(&self) -> B256 {
+        hash_words(&[
+            keccak256(Self::order_type()).0,
+            self.info.eip712_hash_struct().0,
+            self.decayStartTime.to_be_bytes(),
+            self.decayEndTime.to_be_bytes(),
+            address_word(self.exclusiveFiller),
+            self.exclusivityOverrideBps.to_be_bytes(),
+            address_word(self.input.token),
+            self.input.startAmount.to_be_bytes(),
+            self.input.endAmount.to_be_bytes(),
+            hash_structs(&self.outputs).0,
+        ])
+    }
+
+    fn permitted(&self) -> (Address, U256) {
+        (self.input.token, self.input.endAmount)
+    }
+}
+
+impl Permit2Witness for LimitOrder {
+    const WITNESS_NAME: &'static str = "LimitOrder";
+
+    fn order_type() -> String {
+        [LIMIT_ORDER_TYPE, ORDER_INFO_TYPE, OUTPUT_TOKEN_TYPE].concat()
+    }
+
+    fn order_hash(&self) -> B256 {
+        hash_words(&[
+            keccak256(Self::order_type()).0,
+            self.info.eip712_hash_struct().0,
+            address_word(self.input.token),
+            self.input.amount.to_be_bytes(),
+            hash_structs(&self.outputs).0,
+        ])
+    }
+
+    fn permitted(&self) -> (Address, U256) {
+        (self.input.token, self.input.maxAmount)
+    }
+}
+
+/// the eip712 domain of a permit2 deployment
+pub fn permit2_domain(chain_id: u64, permit2: Address) -> Eip712Domain {
+    Eip712Domain {
+        name: Some(Cow::Borrowed("Permit2")),
+        chain_id: Some(U256::from(chain_id)),
+        verifying_contract: Some(permit2),
+        ..Default::default()
+    }
+}
+
+/// the canonical permit2 address, see [PERMIT2_ADDRESS]
+pub fn permit2_address() -> Address {
+    PERMIT2_ADDRESS.parse().expect("permit2 address to parse")
+}
+
+/// the digest the swapper signs, see `PermitHash.hashWithWitness`
+///
+/// `spender` is the reactor of the order, and `nonce`/`deadline` are taken from the order info
+pub fn permit_witness_digest(
+    domain: &Eip712Domain,
+    permitted: (Address, U256),
+    spender: Address,
+    nonce: U256,
+    deadline: U256,
+    witness: B256,
+    witness_type_string: &str,
+) -> B256 {
+    let type_hash =
+        keccak256([PERMIT_WITNESS_TRANSFER_FROM_TYPE_STUB, witness_type_string].concat());
+
+    let token_permissions_hash = hash_words(&[
+        keccak256(TOKEN_PERMISSIONS_TYPE).0,
+        address_word(permitted.0),
+        permitted.1.to_be_bytes(),
+    ]);
+
+    let struct_hash = hash_words(&[
+        type_hash.0,
+        token_permissions_hash.0,
+        address_word(spender),
+        nonce.to_be_bytes(),
+        deadline.to_be_bytes(),
+        witness.0,
+    ]);
+
+    let mut digest_input = Vec::with_capacity(66);
+    digest_input.extend_from_slice(&[0x19, 0x01]);
+    digest_input.extend_from_slice(domain.separator().as_slice());
+    digest_input.extend_from_slice(struct_hash.as_slice());
+
+    keccak256(digest_input)
+}
+
+/// the result of checking a [crate::order::SignedOrder]'s signature against its swapper
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// the signature recovers to the swapper
+    Valid,
+    /// the signature recovers to some other address
+    SignerMismatch(Address),
+    /// the swapper is a contract and its EIP-1271 `isValidSignature` didnt return the magic value
+    InvalidContractSignature,
+    /// the signature cant be decoded or recovered
+    Malformed,
+}
+
+/// parse a hex encoded ecdsa signature, as permit2 does, both 65 byte and 64 byte EIP-2098 signatures are accepted
+pub fn parse_signature(sig: &str) -> Result<Signature, SignatureError> {
+    let bytes = hex::decode(sig.trim_start_matches("0x"))
+        .map_err(|_| SignatureError::InvalidLength(sig.len()))?;
+
+    match bytes.len() {
+        65 => Signature::try_from(bytes.as_slice()),
+        64 => {
+            let r = EthersU256::from_big_endian(&bytes[..32]);
+            let vs = EthersU256::from_big_endian(&bytes[32..]);
+            let s = vs & ((EthersU256::one() << 255) - 1);
+            let v = (vs >> 255).as_u64() + 27;
+
+            Ok(Signature { r, s, v })
+        }
+        len => Err(SignatureError::InvalidLength(len)),
+    }
+}
+
+/// the calldata of an EIP-1271 `isValidSignature(bytes32,bytes)` call, as permit2 makes for contract swappers
+pub fn is_valid_signature_calldata(hash: B256, sig: &[u8]) -> Vec<u8> {
+    let args = ethers::abi::encode(&[Token::FixedBytes(hash.to_vec()), Token::Bytes(sig.to_vec())]);
+
+    [EIP1271_MAGIC_VALUE.as_slice(), args.as_slice()].concat()
+}
+
+/// keccak256 of the abi encoded words
+fn hash_words(words: &[[u8; 32]]) -> B256 {
+    keccak256(words.concat())
+}
+
+/// the eip712 encoding of an array of structs
+fn hash_structs<T: SolStruct>(structs: &[T]) -> B256 {
+    keccak256(
+        structs
+            .iter()
+            .flat_map(|s| s.eip712_hash_struct().0)
+            .collect::<Vec<_>>(),
+    )
+}
+
+fn address_word(address: Address) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(address.as_slice());
+    word
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{order::OrderType, test_utils::*};
+    use uniswapx_ethers_bindings::{
+        dutch_order_reactor::DUTCHORDERREACTOR_DEPLOYED_BYTECODE,
+        exclusive_dutch_order_reactor::EXCLUSIVEDUTCHORDERREACTOR_DEPLOYED_BYTECODE,
+        limit_order_reactor::LIMITORDERREACTOR_DEPLOYED_BYTECODE,
+    };
+
+    fn b256(hex: &str) -> B256 {
+        hex.parse().unwrap()
+    }
+
+    fn contains(code: &[u8], needle: &str) -> bool {
+        code.windows(needle.len()).any(|w| w == needle.as_bytes())
+    }
+
+    /// the pieces solidity concatenates a type string from, e.g. `DutchOrder(`, `OrderInfo info,`, ..
+    fn fragments(ty: &str) -> Vec<&str> {
+        ty.split_inclusive(['(', ',']).collect()
+    }
+
+    /// the reactors build their type strings at runtime from literals in the bytecode,
+    /// so each literal our type strings are made of has to show up there
+    fn assert_in_bytecode(code: &[u8], order_type: &str, referenced: &[&str], witness_name: &str) {
+        for fragment in fragments(order_type) {
+            assert!(contains(code, fragment), "{fragment:?} not in the reactor");
+        }
+        for ty in referenced {
+            assert!(contains(code, ty), "{ty:?} not in the reactor");
+        }
+        assert!(contains(code, &format!("{witness_name} witness)")));
+    }
+
+    #[test]
+    fn type_strings_match_the_reactors() {
+        assert_in_bytecode(
+            &DUTCHORDERREACTOR_DEPLOYED_BYTECODE,
+            DUTCH_ORDER_TYPE,
+            &[DUTCH_OUTPUT_TYPE, ORDER_INFO_TYPE, TOKEN_PERMISSIONS_TYPE],
+            DutchOrder::WITNESS_NAME,
+        );
+        assert_in_bytecode(
+            &EXCLUSIVEDUTCHORDERREACTOR_DEPLOYED_BYTECODE,
+            EXCLUSIVE_DUTCH_ORDER_TYPE,
+            &[DUTCH_OUTPUT_TYPE, ORDER_INFO_TYPE, TOKEN_PERMISSIONS_TYPE],
+            ExclusiveDutchOrder::WITNESS_NAME,
+        );
+        assert_in_bytecode(
+            &LIMITORDERREACTOR_DEPLOYED_BYTECODE,
+            LIMIT_ORDER_TYPE,
+            &[OUTPUT_TOKEN_TYPE, ORDER_INFO_TYPE, TOKEN_PERMISSIONS_TYPE],
+            LimitOrder::WITNESS_NAME,
+        );
+    }
+
+    #[test]
+    fn token_permissions_type_hash() {
+        // `PermitHash._TOKEN_PERMISSIONS_TYPEHASH`
+        assert_eq!(
+            keccak256(TOKEN_PERMISSIONS_TYPE),
+            b256("618358ac3db8dc274f0cd8829da7e234bd48cd73c4a740aede1adec9846d06a1")
+        );
+    }
+
+    /// pinned so a change to the encoding cant go unnoticed, the signatures are of [KEY] on mainnet
+    #[test]
+    fn order_hash_and_digest_vectors() {
+        let vectors = [
+            (
+                OrderType::Dutch,
+                b256("20e93b1efcd036264af07f324837229159dba7a430132577cf6709dc8b1b017d"),
+                b256("a928920595134cd5c6954714c0bbe7f985264c8b5c3e60057e754d38ac4e8819"),
+                "0xc9c57a12385a1795a1f46235892e4e6733700781408be020d51edb1352945623786ddee42ca8d6f609c38a987da1e4266803d1e682c868c5c12cccf3729a57a01b",
+            ),
+            (
+                OrderType::ExclusiveDutch,
+                b256("38f624e0576a104dc97e8876bd5a76c5f379617c7e4d56c2c217e786effa4707"),
+                b256("6c3ecd4de9751c6e34ca0a77b2d3f777bac5194bc1b44bfd57c94f05257867a8"),
+                "0x122aa79a5b786a0697f679e321fae9b518e17df5791925e37f93317388ab0743442f1ef72643996c3a62a3481675a09e14a3dc627ae504eadae6298b1d2f6b361c",
+            ),
+            (
+                OrderType::Limit,
+                b256("58cbc157780ae4c9fd7b1775bad92c008dfea2f42e60f202997517c277b767ea"),
+                b256("9a423913ee9e59708daf903a0cb2668d0bb28fa5702fe4b70aebc807989b78e6"),
+                "0xa779972131c9ad51873d9905b05017d382c543916fea780e525ae2004a484f832752b4b5241d0a56df622e2f305d2b145fa369e9efbc6582c8f271b7c0a21b1c1b",
+            ),
+        ];
+
+        for (order, (order_type, hash, digest, sig)) in orders().into_iter().zip(vectors) {
+            assert_eq!(order.order_type(), order_type);
+            assert_eq!(order.order_hash(), hash);
+            assert_eq!(order.permit2_digest(1, permit2_address()), digest);
+
+            let signed = order.sign(&wallet(), &config()).unwrap();
+            assert_eq!(signed.sig, sig);
+            assert_eq!(
+                parse_signature(sig).unwrap().recover(digest.0).unwrap().0,
+                swapper().0
+            );
+        }
+    }
+
+    #[test]
+    fn parses_65_and_64_byte_signatures() {
+        for order in orders() {
+            let sig = order.sign(&wallet(), &config()).unwrap().sig;
+            let full = parse_signature(&sig).unwrap();
+
+            // EIP-2098, the parity of v goes in the top bit of s
+            let mut compact = hex::decode(&sig[2..]).unwrap();
+            let v = compact.pop().unwrap();
+            if v == 28 {
+                compact[32] |= 0x80;
+            }
+
+            assert_eq!(full.to_vec(), hex::decode(&sig[2..]).unwrap());
+            assert_eq!(
+                parse_signature(&hex::encode(compact)).unwrap(),
+                full,
+                "v {v}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_bad_signatures() {
+        assert!(matches!(
+            parse_signature("0x1234"),
+            Err(SignatureError::InvalidLength(2))
+        ));
+        assert!(matches!(
+            parse_signature(&format!("0x{}", "00".repeat(66))),
+            Err(SignatureError::InvalidLength(66))
+        ));
+        assert!(matches!(
+            parse_signature("0xnothex"),
+            Err(SignatureError::InvalidLength(_))
+        ));
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{
+    config::{ChainConfig, MAINNET},
+    contracts::internal::builder::{
+        DutchOrderBuilder, ExclusiveDutchOrderBuilder, LimitOrderBuilder,
+    },
+    order::{Order, OrderType},
+};
+use alloy_primitives::{Address, U256};
+use ethers::signers::{LocalWallet, Signer};
+
+/// a well known test key, never use it for anything real
+pub const KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+/// the input token of the fixtures
+pub const INPUT_TOKEN: Address = Address::repeat_byte(1);
+
+/// the output token of the fixtures
+pub const OUTPUT_TOKEN: Address = Address::repeat_byte(2);
+
+/// the exclusive filler of [exclusive_dutch]
+pub const EXCLUSIVE_FILLER: Address = Address::repeat_byte(9);
+
+/// the reactor of [limit], theres no built in limit reactor, see [limit_config]
+pub const LIMIT_REACTOR: Address = Address::repeat_byte(3);
+
+pub fn wallet() -> LocalWallet {
+    KEY.parse().expect("test key to parse")
+}
+
+/// the address of [wallet]
+pub fn swapper() -> Address {
+    Address::from(wallet().address().0)
+}
+
+pub fn u(n: u64) -> U256 {
+    U256::from(n)
+}
+
+/// the built in mainnet config
+pub fn config() -> ChainConfig {
+    ChainConfig::builtin(MAINNET).expect("builtin mainnet config")
+}
+
+/// [config] with [LIMIT_REACTOR] as the limit reactor
+pub fn limit_config() -> ChainConfig {
+    let mut config = config();
+    config.reactors.insert(OrderType::Limit, LIMIT_REACTOR);
+    config
+}
+
+/// a dutch order on the mainnet reactor, the output decays from 300 to 200 between 1000 and 2000
+pub fn dutch_builder() -> DutchOrderBuilder {
+    DutchOrderBuilder::new()
+        .reactor(config().reactor(OrderType::Dutch).expect("dutch reactor"))
+        .swapper(swapper())
+        .nonce(u(1))
+        .deadline(u(2000))
+        .decay_start_time(u(1000))
+        .decay_end_time(u(2000))
+        .input(INPUT_TOKEN, u(100), u(100))
+        .output(OUTPUT_TOKEN, u(300), u(200), swapper())
+}
+
+/// like [dutch_builder], exclusive to [EXCLUSIVE_FILLER] until 1000 with a 100 bps override
+pub fn exclusive_dutch_builder() -> ExclusiveDutchOrderBuilder {
+    ExclusiveDutchOrderBuilder::new()
+        .reactor(
+            config()
+                .reactor(OrderType::ExclusiveDutch)
+                .expect("exclusive dutch reactor"),
+        )
+        .swapper(swapper())
+        .nonce(u(2))
+        .deadline(u(2000))
+        .decay_start_time(u(1000))
+        .decay_end_time(u(2000))
+        .exclusivity(EXCLUSIVE_FILLER, u(100))
+        .input(INPUT_TOKEN, u(100), u(100))
+        .output(OUTPUT_TOKEN, u(300), u(200), swapper())
+}
+
+/// a limit order of 100 input for 300 output, with additional validation data
+pub fn limit_builder() -> LimitOrderBuilder {
+    LimitOrderBuilder::new()
+        .reactor(LIMIT_REACTOR)
+        .swapper(swapper())
+        .nonce(u(3))
+        .deadline(u(2000))
+        .additional_validation(Address::repeat_byte(7), vec![1, 2, 3])
+        .input(INPUT_TOKEN, u(100))
+        .output(OUTPUT_TOKEN, u(300), swapper())
+}
+
+pub fn dutch() -> Order {
+    dutch_builder().build().expect("dutch fixture to build")
+}
+
+pub fn exclusive_dutch() -> Order {
+    exclusive_dutch_builder()
+        .build()
+        .expect("exclusive dutch fixture to build")
+}
+
+pub fn limit() -> Order {
+    limit_builder().build().expect("limit fixture to build")
+}
+
+/// one order of each type
+pub fn orders() -> Vec<Order> {
+    vec![dutch(), exclusive_dutch(), limit()]
+}
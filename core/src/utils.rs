@@ -1,11 +1,31 @@
-use crate::order::{SignedOrder, ValidationStatus};
+use crate::{
+    config::ChainConfig,
+    order::{SignedOrder, ValidationStatus},
+    validation::OfflineCheck,
+};
+use alloy_primitives::{Address, B256, U256};
 use ethers::providers::Middleware;
-use std::{collections::HashMap, sync::Arc};
-use tokio::{select, signal, spawn, sync::Mutex, task::JoinHandle};
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, HashSet, VecDeque},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    select, signal, spawn,
+    sync::{Mutex, MutexGuard, Notify},
+    task::JoinHandle,
+};
 
 #[allow(unused_imports)]
 use tracing::{debug, error, info, trace, warn};
 
+/// the longest the eviction task sleeps without checking the deadlines, e.g. if the clock jumps
+const MAX_EVICTION_SLEEP: Duration = Duration::from_secs(60);
+
+/// how many order hashes evicted for good are remembered, see [CachedOrders::is_tombstoned]
+const MAX_TOMBSTONES: usize = 100_000;
+
 /// useful for when you want to spawn a task that doesnt return usally
 ///
 /// this task will return when a ctrl-c is received
@@ -34,48 +54,187 @@ where
     }
 }
 
+/// the current unix timestamp in seconds, as used for order deadlines
+pub fn unix_timestamp() -> U256 {
+    U256::from(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time after unix epoch")
+            .as_secs(),
+    )
+}
+
+/// why an order left the [OrderCache]
+#[derive(Debug)]
+pub enum EvictionReason {
+    /// the deadline passed
+    Expired,
+    /// failed a check that needs no chain state
+    OfflineInvalid(Vec<OfflineCheck>),
+    /// the order quoter rejected it during a flush
+    Invalid(ValidationStatus),
+    /// filled on chain, see [CachedOrders::remove_filled]
+    Filled { filler: Address, block_number: u64 },
+    /// removed with [CachedOrders::remove] by a consumer
+    Removed,
+}
+
+impl EvictionReason {
+    /// if the order can never be valid again, its deadline passed or its nonce was used, so it shouldnt be cached again
+    ///
+    /// signature and reactor failures arent final, an EIP-1271 wallet or a config change can make the order valid
+    pub fn is_final(&self) -> bool {
+        match self {
+            EvictionReason::Expired | EvictionReason::Filled { .. } => true,
+            EvictionReason::OfflineInvalid(failures) => failures.contains(&OfflineCheck::Expired),
+            EvictionReason::Invalid(status) => {
+                matches!(
+                    status,
+                    ValidationStatus::Expired | ValidationStatus::NonceUsed
+                )
+            }
+            EvictionReason::Removed => false,
+        }
+    }
+}
+
+/// called with every order evicted from an [OrderCache], see [OrderCache::on_evict]
+pub type EvictionCallback = Arc<dyn Fn(&SignedOrder, &EvictionReason) + Send + Sync>;
+
 /// a task safe cache meant to be shared across subscribers
 /// it is instanitaed with a [tokio::task] to flush itself periodically
+///
+/// orders are keyed by their [crate::order::Order::order_hash],
+/// and evicted without an rpc call as soon as their deadline passes
 pub struct OrderCache {
-    cache: Mutex<HashMap<String, SignedOrder>>,
+    cache: Mutex<CachedOrders>,
+}
+
+/// the orders in an [OrderCache], reads deref to the underlying map
+///
+/// writes go through [CachedOrders::insert] and [CachedOrders::remove] so the deadline index stays in sync
+pub struct CachedOrders {
+    orders: HashMap<B256, SignedOrder>,
+    /// a min heap of `(deadline, order hash)`, entries for removed orders are skipped when popped
+    deadlines: BinaryHeap<Reverse<(U256, B256)>>,
+    /// wakes the eviction task when theres a new deadline
+    deadline_added: Arc<Notify>,
+    on_evict: Option<EvictionCallback>,
+    /// evictions waiting for the lock to be released before the callback is called with them
+    evicted: Vec<(SignedOrder, EvictionReason)>,
+    /// the hashes of orders evicted for good, the oldest are forgotten first once theres [MAX_TOMBSTONES]
+    tombstones: HashSet<B256>,
+    tombstone_queue: VecDeque<B256>,
+}
+
+/// the locked [CachedOrders] of an [OrderCache], see [OrderCache::lock]
+///
+/// the [OrderCache::on_evict] callback is called for any orders evicted while locked once this is dropped
+pub struct CacheGuard<'a> {
+    guard: Option<MutexGuard<'a, CachedOrders>>,
 }
 
 impl OrderCache {
-    /// spawns a task that flushes the cache every `flush_interval` seconds
+    /// spawns a task that flushes the cache every `flush_interval` seconds,
+    /// and one that evicts orders once their deadline passes
     ///
-    /// uses the [Middleware] to validate orders
-    pub fn new<M: Middleware + 'static>(provider: Arc<M>, flush_interval: u64) -> Arc<Self> {
+    /// uses the [Middleware] and the contracts in `config` to validate orders
+    pub fn new<M: Middleware + 'static>(
+        provider: Arc<M>,
+        config: ChainConfig,
+        flush_interval: u64,
+    ) -> Arc<Self> {
         let new = Arc::new(Self {
-            cache: Mutex::new(HashMap::new()),
+            cache: Mutex::new(CachedOrders {
+                orders: HashMap::new(),
+                deadlines: BinaryHeap::new(),
+                deadline_added: Arc::new(Notify::new()),
+                on_evict: None,
+                evicted: Vec::new(),
+                tombstones: HashSet::new(),
+                tombstone_queue: VecDeque::new(),
+            }),
         });
 
-        Self::spawn_flush_task(new.clone(), provider, flush_interval);
+        Self::spawn_flush_task(new.clone(), provider, config, flush_interval);
+        Self::spawn_eviction_task(new.clone());
 
         new
     }
 
+    /// lock the orders
+    pub async fn lock(&self) -> CacheGuard<'_> {
+        CacheGuard {
+            guard: Some(self.cache.lock().await),
+        }
+    }
+
+    /// call `f` with every order thats evicted and why, replacing any previous callback
+    ///
+    /// `f` is called once the cache is unlocked, so it can lock the cache itself
+    pub async fn on_evict<F>(&self, f: F)
+    where
+        F: Fn(&SignedOrder, &EvictionReason) + Send + Sync + 'static,
+    {
+        self.lock().await.on_evict = Some(Arc::new(f));
+    }
+
     /// flushes the cache, removing invalid orders
-    pub async fn flush<M>(self: Arc<Self>, provider: std::sync::Arc<M>)
+    ///
+    /// orders that fail [crate::order::Order::validate_offline_with] are removed without an rpc call,
+    /// expired orders are usually gone already, see [OrderCache::new]
+    ///
+    /// the signature isnt checked offline, as contract swappers sign with EIP-1271, the order quoter checks it instead
+    ///
+    /// the orders are validated against a snapshot of the cache, so its only locked to take the snapshot and evict
+    pub async fn flush<M>(self: Arc<Self>, provider: std::sync::Arc<M>, config: &ChainConfig)
     where
         M: Middleware + 'static,
     {
-        let mut lock = self.cache.lock().await;
-
-        let (keys, futures): (Vec<_>, Vec<_>) = lock
+        let snapshot: Vec<(B256, SignedOrder)> = self
+            .lock()
+            .await
             .iter()
-            .map(|(k, order)| (k.clone(), order.validate_ethers(provider.clone())))
-            .unzip();
+            .map(|(key, order)| (*key, order.clone()))
+            .collect();
+
+        let now = unix_timestamp();
+        let (valid, offline_invalid): (Vec<_>, Vec<_>) = snapshot
+            .into_iter()
+            .map(|(key, order)| {
+                let report = order.order.validate_offline_with(now, config);
+                (key, order, report)
+            })
+            .partition(|(_, _, report)| report.is_valid());
+
+        if !offline_invalid.is_empty() {
+            let mut lock = self.lock().await;
+
+            for (key, _, report) in offline_invalid {
+                info!(
+                    "order {} failed offline validation, removing: {:?}",
+                    key, report.failures
+                );
+                lock.evict(&key, EvictionReason::OfflineInvalid(report.failures));
+            }
+        }
 
-        let results = futures::future::join_all(futures).await;
+        let results = futures::future::join_all(
+            valid
+                .iter()
+                .map(|(_, order, _)| order.validate_ethers(provider.clone(), config)),
+        )
+        .await;
 
-        for (key, result) in keys.into_iter().zip(results.into_iter()) {
+        let mut invalid = Vec::new();
+        for ((key, _, _), result) in valid.into_iter().zip(results) {
             match result {
                 Ok(ValidationStatus::OK) => {
                     info!("order {} is valid, keeping", key);
                 }
-                Ok(_) => {
+                Ok(status) => {
                     info!("order {} is invalid, removing", key);
-                    lock.remove(&key);
+                    invalid.push((key, status));
                 }
                 Err(e) => {
                     error!(
@@ -86,35 +245,457 @@ impl OrderCache {
             }
         }
 
-        // explicity drop lock for sanity
-        drop(lock);
+        if !invalid.is_empty() {
+            let mut lock = self.lock().await;
+
+            for (key, status) in invalid {
+                lock.evict(&key, EvictionReason::Invalid(status));
+            }
+        }
     }
 
     // doesnt return a join handle but is spawned with shutdown
-    fn spawn_flush_task<M>(self: Arc<Self>, provider: Arc<M>, flush_interval: u64)
-    where
+    fn spawn_flush_task<M>(
+        self: Arc<Self>,
+        provider: Arc<M>,
+        config: ChainConfig,
+        flush_interval: u64,
+    ) where
         M: Middleware + 'static,
     {
         spawn_with_shutdown(async move {
             loop {
                 tokio::time::sleep(tokio::time::Duration::from_secs(flush_interval)).await;
                 info!("flushing cache");
-                self.clone().flush(provider.clone()).await;
+                self.clone().flush(provider.clone(), &config).await;
+            }
+        });
+    }
+
+    // sleeps until the soonest deadline passes, or a new order is inserted
+    fn spawn_eviction_task(self: Arc<Self>) {
+        spawn_with_shutdown(async move {
+            let deadline_added = self.lock().await.deadline_added.clone();
+
+            loop {
+                let next = self.lock().await.evict_expired(unix_timestamp());
+
+                // an order is expired once the current second is past its deadline
+                let wait = match next {
+                    Some(deadline) => {
+                        let at = u64::try_from(deadline)
+                            .unwrap_or(u64::MAX)
+                            .saturating_add(1);
+
+                        (UNIX_EPOCH + Duration::from_secs(at))
+                            .duration_since(SystemTime::now())
+                            .unwrap_or_default()
+                            .min(MAX_EVICTION_SLEEP)
+                    }
+                    None => MAX_EVICTION_SLEEP,
+                };
+
+                select! {
+                    _ = tokio::time::sleep(wait) => {}
+                    _ = deadline_added.notified() => {}
+                }
             }
         });
     }
 }
 
-impl std::ops::Deref for OrderCache {
-    type Target = Mutex<HashMap<String, SignedOrder>>;
+impl CachedOrders {
+    /// cache an order, returns false if it was already cached or was evicted for good
+    pub fn insert(&mut self, order: SignedOrder) -> bool {
+        let hash = order.order_hash();
+
+        if self.is_tombstoned(&hash) {
+            return false;
+        }
+
+        match self.orders.entry(hash) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                let deadline = order.deadline();
+                entry.insert(order);
+
+                let earliest = match self.next_deadline() {
+                    Some(next) => deadline < next,
+                    None => true,
+                };
+
+                if earliest {
+                    self.deadline_added.notify_one();
+                }
+                self.deadlines.push(Reverse((deadline, hash)));
+
+                true
+            }
+        }
+    }
+
+    /// if the order was evicted for good, see [EvictionReason::is_final]
+    ///
+    /// only the last [MAX_TOMBSTONES] of these are remembered
+    pub fn is_tombstoned(&self, hash: &B256) -> bool {
+        self.tombstones.contains(hash)
+    }
+
+    /// remove an order, the eviction callback is called with [EvictionReason::Removed]
+    pub fn remove(&mut self, hash: &B256) -> Option<SignedOrder> {
+        self.evict(hash, EvictionReason::Removed)
+    }
+
+    /// remove an order thats been filled on chain, e.g. from a reactors `Fill` event
+    pub fn remove_filled(
+        &mut self,
+        hash: &B256,
+        filler: Address,
+        block_number: u64,
+    ) -> Option<SignedOrder> {
+        self.evict(
+            hash,
+            EvictionReason::Filled {
+                filler,
+                block_number,
+            },
+        )
+    }
+
+    pub(crate) fn evict(&mut self, hash: &B256, reason: EvictionReason) -> Option<SignedOrder> {
+        let order = self.orders.remove(hash)?;
+        debug!("order {} evicted: {:?}", hash, reason);
+
+        if reason.is_final() {
+            self.tombstone(*hash);
+        }
+
+        // the callback is called by the [CacheGuard] once the lock is released
+        if self.on_evict.is_some() {
+            self.evicted.push((order.clone(), reason));
+        }
+
+        // dont let the heap fill up with removed orders
+        if self.deadlines.len() > 2 * self.orders.len() + 64 {
+            let orders = &self.orders;
+            self.deadlines
+                .retain(|Reverse((_, hash))| orders.contains_key(hash));
+        }
+
+        Some(order)
+    }
+
+    /// evict every order whose deadline is before `now`, returns the next deadline
+    fn evict_expired(&mut self, now: U256) -> Option<U256> {
+        while let Some(Reverse((deadline, hash))) = self.deadlines.peek().copied() {
+            if deadline >= now {
+                return Some(deadline);
+            }
+
+            self.deadlines.pop();
+            self.evict(&hash, EvictionReason::Expired);
+        }
+
+        None
+    }
+
+    fn tombstone(&mut self, hash: B256) {
+        if !self.tombstones.insert(hash) {
+            return;
+        }
+
+        self.tombstone_queue.push_back(hash);
+        if self.tombstone_queue.len() > MAX_TOMBSTONES {
+            if let Some(oldest) = self.tombstone_queue.pop_front() {
+                self.tombstones.remove(&oldest);
+            }
+        }
+    }
+
+    fn next_deadline(&self) -> Option<U256> {
+        self.deadlines
+            .peek()
+            .map(|Reverse((deadline, _))| *deadline)
+    }
+}
+
+impl std::ops::Deref for CachedOrders {
+    type Target = HashMap<B256, SignedOrder>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.orders
+    }
+}
+
+impl std::ops::Deref for CacheGuard<'_> {
+    type Target = CachedOrders;
 
     fn deref(&self) -> &Self::Target {
-        &self.cache
+        self.guard.as_ref().expect("guard is only taken on drop")
     }
 }
 
-impl std::ops::DerefMut for OrderCache {
+impl std::ops::DerefMut for CacheGuard<'_> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.cache
+        self.guard.as_mut().expect("guard is only taken on drop")
+    }
+}
+
+impl Drop for CacheGuard<'_> {
+    fn drop(&mut self) {
+        let mut guard = match self.guard.take() {
+            Some(guard) => guard,
+            None => return,
+        };
+
+        let evicted = std::mem::take(&mut guard.evicted);
+        let on_evict = guard.on_evict.clone();
+        drop(guard);
+
+        if let Some(on_evict) = on_evict {
+            for (order, reason) in evicted {
+                on_evict(&order, &reason);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        signing::SignatureStatus,
+        test_utils::{self, dutch_builder, wallet},
+    };
+    use ethers::{
+        abi::AbiEncode,
+        providers::{MockError, MockProvider, Provider},
+        types::Bytes,
+    };
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tokio::sync::mpsc;
+    use uniswapx_ethers_bindings::order_quoter::ResolvedOrder as EthersResolvedOrder;
+
+    fn order() -> SignedOrder {
+        dutch_builder()
+            .deadline(U256::from(u64::MAX))
+            .build()
+            .unwrap()
+            .signed("0x".to_string())
+    }
+
+    fn cache() -> Arc<OrderCache> {
+        let (provider, _mock) = Provider::mocked();
+
+        OrderCache::new(Arc::new(provider), test_utils::config(), 3600)
+    }
+
+    #[tokio::test]
+    async fn on_evict_is_called_unlocked() {
+        let cache = cache();
+
+        let unlocked = Arc::new(AtomicBool::new(false));
+        let weak = Arc::downgrade(&cache);
+        let flag = unlocked.clone();
+        cache
+            .on_evict(move |_, reason| {
+                assert!(matches!(reason, EvictionReason::Removed));
+
+                let cache = weak.upgrade().unwrap();
+                flag.store(cache.cache.try_lock().is_ok(), Ordering::SeqCst);
+            })
+            .await;
+
+        let order = order();
+        let hash = order.order_hash();
+        assert!(cache.lock().await.insert(order));
+        assert!(cache.lock().await.remove(&hash).is_some());
+
+        assert!(unlocked.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn filled_orders_arent_cached_again() {
+        let cache = cache();
+        let order = order();
+        let hash = order.order_hash();
+
+        let mut lock = cache.lock().await;
+        assert!(lock.insert(order.clone()));
+        assert!(lock
+            .remove_filled(&hash, Address::repeat_byte(9), 1)
+            .is_some());
+
+        assert!(lock.is_tombstoned(&hash));
+        assert!(!lock.insert(order));
+    }
+
+    #[tokio::test]
+    async fn removed_orders_can_be_cached_again() {
+        let cache = cache();
+        let order = order();
+        let hash = order.order_hash();
+
+        let mut lock = cache.lock().await;
+        assert!(lock.insert(order.clone()));
+        assert!(lock.remove(&hash).is_some());
+
+        assert!(!lock.is_tombstoned(&hash));
+        assert!(lock.insert(order));
+    }
+
+    #[tokio::test]
+    async fn only_terminal_evictions_are_final() {
+        let cache = cache();
+        let order = order();
+        let hash = order.order_hash();
+
+        let retryable = [
+            EvictionReason::OfflineInvalid(vec![
+                OfflineCheck::UnknownReactor(Address::repeat_byte(8)),
+                OfflineCheck::InvalidSignature(SignatureStatus::Malformed),
+            ]),
+            EvictionReason::Invalid(ValidationStatus::InvalidSignature),
+            EvictionReason::Invalid(ValidationStatus::InvalidOrderFields),
+            EvictionReason::Invalid(ValidationStatus::InsufficientFunds),
+        ];
+        for reason in retryable {
+            let mut lock = cache.lock().await;
+            assert!(lock.insert(order.clone()));
+            assert!(lock.evict(&hash, reason).is_some());
+            assert!(!lock.is_tombstoned(&hash));
+        }
+
+        let terminal = [
+            EvictionReason::OfflineInvalid(vec![OfflineCheck::ZeroSwapper, OfflineCheck::Expired]),
+            EvictionReason::Invalid(ValidationStatus::Expired),
+            EvictionReason::Invalid(ValidationStatus::NonceUsed),
+        ];
+        for reason in terminal {
+            assert!(reason.is_final());
+        }
+
+        let mut lock = cache.lock().await;
+        assert!(lock.insert(order.clone()));
+        assert!(lock
+            .evict(&hash, EvictionReason::Invalid(ValidationStatus::NonceUsed))
+            .is_some());
+        assert!(lock.is_tombstoned(&hash));
+        assert!(!lock.insert(order));
+    }
+
+    #[tokio::test]
+    async fn flush_keeps_contract_swappers() {
+        let config = test_utils::config();
+        let (provider, mock) = Provider::mocked();
+        let provider = Arc::new(provider);
+        let cache = OrderCache::new(provider.clone(), config.clone(), 3600);
+
+        // signed by a key thats not the swapper, as an EIP-1271 wallet would
+        let order = dutch_builder()
+            .swapper(Address::repeat_byte(0xc0))
+            .deadline(U256::from(u64::MAX))
+            .build()
+            .unwrap()
+            .sign(&wallet(), &config)
+            .unwrap();
+        let hash = order.order_hash();
+        assert!(matches!(
+            order.verify(&config),
+            SignatureStatus::SignerMismatch(_)
+        ));
+
+        let evicted = Arc::new(AtomicBool::new(false));
+        let flag = evicted.clone();
+        cache
+            .on_evict(move |_, _| flag.store(true, Ordering::SeqCst))
+            .await;
+
+        // the order quoter accepts it
+        let quote = Bytes::from(EthersResolvedOrder::default().encode());
+        mock.push::<Bytes, _>(quote.clone()).unwrap();
+        mock.push::<Bytes, _>(quote).unwrap();
+        assert!(matches!(
+            order.validate_ethers(provider.clone(), &config).await,
+            Ok(ValidationStatus::OK)
+        ));
+        assert!(cache.lock().await.insert(order));
+
+        cache.clone().flush(provider, &config).await;
+
+        assert!(cache.lock().await.contains_key(&hash));
+        assert!(!evicted.load(Ordering::SeqCst));
+    }
+
+    /// a cache that sends the hash of every order it evicts as expired, and if it was unlocked at the time
+    async fn expiring_cache() -> (
+        Arc<OrderCache>,
+        MockProvider,
+        mpsc::UnboundedReceiver<(B256, bool)>,
+    ) {
+        let (provider, mock) = Provider::mocked();
+        let cache = OrderCache::new(Arc::new(provider), test_utils::config(), 3600);
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let weak = Arc::downgrade(&cache);
+        cache
+            .on_evict(move |order, reason| {
+                assert!(matches!(reason, EvictionReason::Expired));
+
+                let cache = weak.upgrade().unwrap();
+                let unlocked = cache.cache.try_lock().is_ok();
+                tx.send((order.order_hash(), unlocked)).unwrap();
+            })
+            .await;
+
+        (cache, mock, rx)
+    }
+
+    #[tokio::test]
+    async fn orders_are_evicted_once_past_their_deadline() {
+        let (cache, mock, mut rx) = expiring_cache().await;
+        let order = dutch_builder().build().unwrap().signed("0x".to_string());
+        let hash = order.order_hash();
+
+        // held the whole time so the eviction task cant get to it first
+        let mut lock = cache.lock().await;
+        assert!(lock.insert(order.clone()));
+
+        // the deadline is 2000
+        assert_eq!(lock.evict_expired(U256::from(2000)), Some(U256::from(2000)));
+        assert!(lock.contains_key(&hash));
+
+        assert_eq!(lock.evict_expired(U256::from(2001)), None);
+        assert!(!lock.contains_key(&hash));
+        assert!(lock.is_tombstoned(&hash));
+        assert!(!lock.insert(order));
+
+        // the callback waits for the lock to be released
+        assert!(rx.try_recv().is_err());
+        drop(lock);
+        assert_eq!(rx.try_recv().unwrap(), (hash, true));
+
+        assert!(matches!(
+            mock.assert_request("eth_call", ()),
+            Err(MockError::EmptyRequests)
+        ));
+    }
+
+    #[tokio::test]
+    async fn the_eviction_task_evicts_expired_orders() {
+        let (cache, mock, mut rx) = expiring_cache().await;
+        let order = dutch_builder().build().unwrap().signed("0x".to_string());
+        let hash = order.order_hash();
+
+        assert!(cache.lock().await.insert(order));
+
+        let evicted = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await;
+        assert_eq!(evicted.unwrap(), Some((hash, true)));
+        assert!(!cache.lock().await.contains_key(&hash));
+
+        assert!(matches!(
+            mock.assert_request("eth_call", ()),
+            Err(MockError::EmptyRequests)
+        ));
     }
 }
@@ -0,0 +1,312 @@
+use crate::{
+    config::ChainConfig,
+    contracts::{
+        common::OrderInfo,
+        internal::{
+            dutch::{DutchInput, DutchOrder, DutchOutput},
+            exclusive_dutch::ExclusiveDutchOrder,
+            limit::LimitOrder,
+        },
+    },
+    order::{Order, ValidationStatus},
+    signing::SignatureStatus,
+};
+use alloy_primitives::{Address, U256};
+
+/// a single check that failed during [Order::validate_offline]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OfflineCheck {
+    /// the deadline has passed
+    Expired,
+    /// the decay end time is before the decay start time
+    EndTimeBeforeStartTime,
+    /// the deadline is before the decay end time
+    DeadlineBeforeEndTime,
+    /// both the input and an output decay
+    InputAndOutputDecay,
+    /// the input decays downwards or an output decays upwards
+    IncorrectAmounts,
+    /// two outputs have the same token and recipient
+    DuplicateOutput { token: Address, recipient: Address },
+    /// the reactor isnt the configured deployment for this order type and chain
+    UnknownReactor(Address),
+    /// the swapper is the zero address
+    ZeroSwapper,
+    /// the signature doesnt recover to the swapper
+    InvalidSignature(SignatureStatus),
+}
+
+/// every check that failed during offline validation, empty if the order passed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub failures: Vec<OfflineCheck>,
+}
+
+/// a single check that failed during [crate::order::SignedOrder::validate_state_ethers]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateCheck {
+    /// the nonce bit is already set in the swappers permit2 nonce bitmap
+    NonceUsed,
+    /// the swapper holds less of the input token than the order can pull
+    InsufficientBalance,
+    /// the swappers erc20 allowance to permit2 is less than the order can pull
+    InsufficientAllowance,
+}
+
+/// the on chain state of the swapper that decides if permit2 can transfer the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateReport {
+    /// the max input the order can resolve to
+    pub required: U256,
+    /// the swappers balance of the input token
+    pub balance: U256,
+    /// the swappers erc20 allowance to permit2
+    pub allowance: U256,
+    /// the order nonce has been used or cancelled
+    pub nonce_used: bool,
+}
+
+/// the checks that depend on the concrete order type
+pub trait OfflineValidation {
+    fn offline_checks(&self) -> Vec<OfflineCheck>;
+}
+
+impl OfflineValidation for DutchOrder {
+    fn offline_checks(&self) -> Vec<OfflineCheck> {
+        dutch_checks(
+            &self.info,
+            self.decayStartTime,
+            self.decayEndTime,
+            &self.input,
+            &self.outputs,
+        )
+    }
+}
+
+impl OfflineValidation for ExclusiveDutchOrder {
+    fn offline_checks(&self) -> Vec<OfflineCheck> {
+        dutch_checks(
+            &self.info,
+            self.decayStartTime,
+            self.decayEndTime,
+            &self.input,
+            &self.outputs,
+        )
+    }
+}
+
+impl OfflineValidation for LimitOrder {
+    fn offline_checks(&self) -> Vec<OfflineCheck> {
+        duplicate_outputs(self.outputs.iter().map(|o| (o.token, o.recipient)))
+    }
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// the [ValidationStatus] of the first failing check, or [ValidationStatus::OK]
+    pub fn status(&self) -> ValidationStatus {
+        self.failures
+            .first()
+            .map(ValidationStatus::from)
+            .unwrap_or(ValidationStatus::OK)
+    }
+}
+
+impl StateReport {
+    /// every check that failed
+    pub fn failures(&self) -> Vec<StateCheck> {
+        let mut failures = Vec::new();
+
+        if self.nonce_used {
+            failures.push(StateCheck::NonceUsed);
+        }
+
+        if self.balance < self.required {
+            failures.push(StateCheck::InsufficientBalance);
+        }
+
+        if self.allowance < self.required {
+            failures.push(StateCheck::InsufficientAllowance);
+        }
+
+        failures
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.failures().is_empty()
+    }
+
+    /// the [ValidationStatus] of the first failing check, or [ValidationStatus::OK]
+    pub fn status(&self) -> ValidationStatus {
+        self.failures()
+            .first()
+            .map(ValidationStatus::from)
+            .unwrap_or(ValidationStatus::OK)
+    }
+}
+
+impl From<&StateCheck> for ValidationStatus {
+    fn from(check: &StateCheck) -> Self {
+        match check {
+            StateCheck::NonceUsed => ValidationStatus::NonceUsed,
+            StateCheck::InsufficientBalance | StateCheck::InsufficientAllowance => {
+                ValidationStatus::InsufficientFunds
+            }
+        }
+    }
+}
+
+impl From<&StateReport> for ValidationStatus {
+    fn from(report: &StateReport) -> Self {
+        report.status()
+    }
+}
+
+impl From<&OfflineCheck> for ValidationStatus {
+    fn from(check: &OfflineCheck) -> Self {
+        match check {
+            OfflineCheck::Expired => ValidationStatus::Expired,
+            OfflineCheck::InvalidSignature(_) => ValidationStatus::InvalidSignature,
+            _ => ValidationStatus::InvalidOrderFields,
+        }
+    }
+}
+
+impl From<&ValidationReport> for ValidationStatus {
+    fn from(report: &ValidationReport) -> Self {
+        report.status()
+    }
+}
+
+/// the checks that apply to every order type
+///
+/// the reactor is checked against `config`, a chain with no config is unknown rather than invalid, so its reactor isnt checked
+///
+/// an order type with no configured reactor is only checked against the other reactors, as they cant fill it
+pub(crate) fn common_checks(
+    order: &Order,
+    now: U256,
+    config: Option<&ChainConfig>,
+) -> Vec<OfflineCheck> {
+    let info = order.info();
+    let mut failures = Vec::new();
+
+    if info.deadline < now {
+        failures.push(OfflineCheck::Expired);
+    }
+
+    if info.swapper == Address::ZERO {
+        failures.push(OfflineCheck::ZeroSwapper);
+    }
+
+    if let Some(config) = config {
+        let unknown = match config.reactor(order.order_type()) {
+            Some(reactor) => reactor != info.reactor,
+            None => !config.order_types_of(info.reactor).is_empty(),
+        };
+
+        if unknown {
+            failures.push(OfflineCheck::UnknownReactor(info.reactor));
+        }
+    }
+
+    failures
+}
+
+/// mirrors the dutch reactors `_validateOrder` and the decay checks in `DutchDecayLib`
+fn dutch_checks(
+    info: &OrderInfo,
+    decay_start_time: U256,
+    decay_end_time: U256,
+    input: &DutchInput,
+    outputs: &[DutchOutput],
+) -> Vec<OfflineCheck> {
+    let mut failures = Vec::new();
+
+    if decay_end_time < decay_start_time {
+        failures.push(OfflineCheck::EndTimeBeforeStartTime);
+    }
+
+    if info.deadline < decay_end_time {
+        failures.push(OfflineCheck::DeadlineBeforeEndTime);
+    }
+
+    if input.startAmount != input.endAmount && outputs.iter().any(|o| o.startAmount != o.endAmount)
+    {
+        failures.push(OfflineCheck::InputAndOutputDecay);
+    }
+
+    if input.startAmount > input.endAmount || outputs.iter().any(|o| o.startAmount < o.endAmount) {
+        failures.push(OfflineCheck::IncorrectAmounts);
+    }
+
+    failures.extend(duplicate_outputs(
+        outputs.iter().map(|o| (o.token, o.recipient)),
+    ));
+
+    failures
+}
+
+fn duplicate_outputs(outputs: impl Iterator<Item = (Address, Address)>) -> Vec<OfflineCheck> {
+    let mut seen = Vec::new();
+    let mut failures = Vec::new();
+
+    for (token, recipient) in outputs {
+        if seen.contains(&(token, recipient)) {
+            failures.push(OfflineCheck::DuplicateOutput { token, recipient });
+        } else {
+            seen.push((token, recipient));
+        }
+    }
+
+    failures
+}
+
+/// the word position and bit mask of a nonce in the permit2 nonce bitmap, see `SignatureTransfer.bitmapPositions`
+pub fn nonce_bitmap_position(nonce: U256) -> (U256, U256) {
+    let word_pos = nonce >> 8;
+    let bit_pos = nonce & U256::from(0xff);
+
+    (word_pos, U256::from(1) << bit_pos.to::<usize>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{order::OrderType, test_utils::*};
+
+    fn unknown_reactor(order: &Order, config: &ChainConfig) -> bool {
+        order
+            .validate_offline_with(u(0), config)
+            .failures
+            .contains(&OfflineCheck::UnknownReactor(order.reactor_address()))
+    }
+
+    #[test]
+    fn unmapped_order_types_arent_accepted_by_other_reactors() {
+        let exclusive_dutch_reactor = config().reactor(OrderType::ExclusiveDutch).unwrap();
+        let at_exclusive_dutch = limit_builder()
+            .reactor(exclusive_dutch_reactor)
+            .build()
+            .unwrap();
+
+        assert_eq!(config().reactor(OrderType::Limit), None);
+        assert!(unknown_reactor(&at_exclusive_dutch, &config()));
+        assert!(!unknown_reactor(&limit(), &config()));
+
+        assert!(unknown_reactor(&at_exclusive_dutch, &limit_config()));
+        assert!(!unknown_reactor(&limit(), &limit_config()));
+    }
+
+    #[test]
+    fn mapped_order_types_need_their_reactor() {
+        let at_limit = dutch_builder().reactor(LIMIT_REACTOR).build().unwrap();
+
+        assert!(!unknown_reactor(&dutch(), &config()));
+        assert!(unknown_reactor(&at_limit, &config()));
+        assert!(unknown_reactor(&at_limit, &limit_config()));
+    }
+}